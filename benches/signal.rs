@@ -0,0 +1,58 @@
+//! Cost of a `Signal` tick as its window grows, next to the `Vec` it used to
+//! keep its points in. Run with `cargo bench --bench signal`.
+
+use missile_launcher::{
+    app::Signal,
+    scenario::SourceConfig,
+    signal::{self, Sampler},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const WINDOWS: [usize; 4] = [100, 1_000, 10_000, 100_000];
+const TICK_RATE: usize = 10;
+const TICKS: u32 = 10_000;
+
+fn sampler() -> Sampler {
+    let config = SourceConfig::Sine {
+        period: 20.0,
+        amplitude: 16.0,
+        noise: 0.1,
+    };
+    Sampler::new(signal::build(&config, StdRng::seed_from_u64(0)), 0.1)
+}
+
+/// Average time of a tick of `tick`.
+fn time(mut tick: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        tick();
+    }
+    start.elapsed() / TICKS
+}
+
+fn main() {
+    println!("{:>8}  {:>12}  {:>12}", "window", "ring buffer", "Vec");
+    for window in WINDOWS {
+        let mut signal = Signal::new(sampler(), window, TICK_RATE);
+        let ring = time(|| {
+            signal.on_tick();
+            black_box(signal.points());
+        });
+
+        let mut source = sampler();
+        let mut points: Vec<_> = source.by_ref().take(window).collect();
+        let vec = time(|| {
+            for _ in 0..TICK_RATE {
+                points.remove(0);
+            }
+            points.extend(source.by_ref().take(TICK_RATE));
+            black_box(points.as_slice());
+        });
+
+        println!("{:>8}  {:>12?}  {:>12?}", window, ring, vec);
+    }
+}
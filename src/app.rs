@@ -1,396 +1,38 @@
-use crossterm::event::KeyCode;
+use crate::{
+    audit::{AuditLog, Outcome},
+    auth::{Attempt, Authorization, Expired},
+    geo,
+    keys::{Action, Key, Keymap},
+    logs::{self, LogEntry, LogLevel},
+    scenario::{
+        ChartConfig, ChecklistItem, LogMessage, Scenario, SignalMarker, TimelineEvent, DEFCON_PEACE,
+    },
+    signal::{self, RingBuffer, Sampler},
+    theme::{parse_color, Theme},
+    timeline::Timeline,
+    traffic::{self, Traffic},
+};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use rand::{
     distributions::{Distribution, Uniform},
-    rngs::ThreadRng,
-    Rng,
+    rngs::StdRng,
+    Rng, SeedableRng,
 };
-use tui::widgets::ListState;
-
-const TASKS: [&str; 4] = ["Item1", "Item2", "Item3", "Item4"];
-
-const LOGS: [(&str, &str); 48] = [
-    ("TPE launch system is ready and stable", "INFO"),
-    ("LAX launch system is ready and stable", "INFO"),
-    ("JFK launch system is ready and stable", "INFO"),
-    (
-        "Unknown connection detected from 140.122.64.120 (Taiwan)",
-        "WARNING",
-    ),
-    ("SFO launch system is ready and stable", "INFO"),
-    ("ORD launch system is ready and stable", "INFO"),
-    ("DFW launch system is ready and stable", "INFO"),
-    (
-        "Lost connection from LAX launch system due to internal errors",
-        "ERROR",
-    ),
-    ("DEFCON alert has been raised to level 3", "CRITICAL"),
-    ("MIA launch system is ready and stable", "INFO"),
-    ("SEA launch system is ready and stable", "INFO"),
-    (
-        "Connection established with ISS (International Space Station)",
-        "INFO",
-    ),
-    ("Received telemetry data from TPE launch system", "INFO"),
-    ("Error reading sensor data from JFK launch system", "ERROR"),
-    ("Power outage detected at LAX launch system", "WARNING"),
-    (
-        "Authentication failure from 192.168.0.1 (internal network)",
-        "WARNING",
-    ),
-    ("Launch countdown initiated for ORD launch system", "INFO"),
-    (
-        "Critical system update installed on DFW launch system",
-        "INFO",
-    ),
-    (
-        "Network congestion observed on SFO launch system",
-        "WARNING",
-    ),
-    (
-        "Data corruption detected in MIA launch system logs",
-        "ERROR",
-    ),
-    ("Launch sequence aborted for SEA launch system", "CRITICAL"),
-    ("System overload on TPE launch system", "WARNING"),
-    ("Launch system reconfigured successfully at JFK", "INFO"),
-    (
-        "Unauthorized access attempt detected on ORD launch system",
-        "WARNING",
-    ),
-    (
-        "Emergency shutdown triggered on LAX launch system",
-        "CRITICAL",
-    ),
-    ("Connection established with weather satellite", "INFO"),
-    ("Error writing log file on DFW launch system", "ERROR"),
-    (
-        "Unexpected response received from SFO launch system",
-        "WARNING",
-    ),
-    ("Insufficient fuel detected in MIA launch system", "ERROR"),
-    ("Launch system rebooted successfully at SEA", "INFO"),
-    (
-        "Unauthorized login attempt from 192.168.0.10 (internal network)",
-        "WARNING",
-    ),
-    (
-        "Critical hardware failure reported by LAX launch system",
-        "CRITICAL",
-    ),
-    ("Security breach detected on SFO launch system", "CRITICAL"),
-    ("Power supply failure on DFW launch system", "ERROR"),
-    (
-        "Communication error with satellite at MIA launch system",
-        "ERROR",
-    ),
-    (
-        "Launch aborted due to inclement weather at SEA launch system",
-        "CRITICAL",
-    ),
-    (
-        "Unauthorized access attempt from external IP 203.120.45.78",
-        "WARNING",
-    ),
-    (
-        "Critical software bug discovered on TPE launch system",
-        "CRITICAL",
-    ),
-    ("Sensor malfunction detected on JFK launch system", "ERROR"),
-    ("Network outage affecting ORD launch system", "WARNING"),
-    ("Launch system initialization completed at LAX", "INFO"),
-    (
-        "Unauthorized login detected on DFW launch system",
-        "WARNING",
-    ),
-    (
-        "Satellite signal loss experienced on SFO launch system",
-        "WARNING",
-    ),
-    ("Fuel leak detected in MIA launch system", "ERROR"),
-    ("Launch system update deployed successfully at SEA", "INFO"),
-    (
-        "Unauthorized network scan detected on TPE launch system",
-        "WARNING",
-    ),
-    (
-        "Critical failure in propulsion system on JFK launch system",
-        "CRITICAL",
-    ),
-    (
-        "Error in communication protocol with ISS on ORD launch system",
-        "ERROR",
-    ),
-];
-
-const EVENTS: [(&str, u64); 48] = [
-    ("TPE", 9),
-    ("LAX", 5),
-    ("JFK", 8),
-    ("SFO", 3),
-    ("ORD", 4),
-    ("DFW", 7),
-    ("MIA", 2),
-    ("SEA", 1),
-    ("CLT", 10),
-    ("LAS", 7),
-    ("PHX", 6),
-    ("MCO", 3),
-    ("IAH", 5),
-    ("EWR", 8),
-    ("ATL", 9),
-    ("LGA", 4),
-    ("DEN", 2),
-    ("BOS", 1),
-    ("SFO", 3),
-    ("PHX", 6),
-    ("MCO", 3),
-    ("IAH", 5),
-    ("EWR", 8),
-    ("ATL", 5),
-    ("LGA", 4),
-    ("DEN", 2),
-    ("BOS", 1),
-    ("SFO", 3),
-    ("PHX", 6),
-    ("MCO", 3),
-    ("IAH", 5),
-    ("EWR", 8),
-    ("ATL", 10),
-    ("LGA", 4),
-    ("DEN", 2),
-    ("BOS", 1),
-    ("SFO", 3),
-    ("PHX", 6),
-    ("MCO", 3),
-    ("IAH", 5),
-    ("EWR", 8),
-    ("ATL", 7),
-    ("LGA", 4),
-    ("DEN", 2),
-    ("BOS", 1),
-    ("SFO", 3),
-    ("PHX", 6),
-    ("MCO", 3),
-];
-
-const LAUNCHERS: [Launcher; 34] = [
-    Launcher {
-        name: "Asia-1",
-        location: "TPE",
-        coords: (25.0094715, 121.5370432),
-        status: "Up",
-    },
-    Launcher {
-        name: "USA-1",
-        location: "LAX",
-        coords: (34.052235, -118.243683),
-        status: "Up",
-    },
-    Launcher {
-        name: "USA-2",
-        location: "JFK",
-        coords: (40.6413111, -73.7781391),
-        status: "Down",
-    },
-    Launcher {
-        name: "USA-3",
-        location: "SFO",
-        coords: (37.7749, -122.4194),
-        status: "Up",
-    },
-    Launcher {
-        name: "USA-4",
-        location: "ORD",
-        coords: (41.9742, -87.9073),
-        status: "Down",
-    },
-    Launcher {
-        name: "USA-5",
-        location: "DFW",
-        coords: (32.8998, -97.0403),
-        status: "Up",
-    },
-    Launcher {
-        name: "USA-6",
-        location: "MIA",
-        coords: (25.7617, -80.1918),
-        status: "Down",
-    },
-    Launcher {
-        name: "USA-7",
-        location: "SEA",
-        coords: (47.6062, -122.3321),
-        status: "Up",
-    },
-    Launcher {
-        name: "USA-8",
-        location: "CLT",
-        coords: (35.2271, -80.8431),
-        status: "Down",
-    },
-    Launcher {
-        name: "USA-9",
-        location: "LAS",
-        coords: (36.1699, -115.1398),
-        status: "Up",
-    },
-    Launcher {
-        name: "Europe-1",
-        location: "LHR",
-        coords: (51.5074, -0.1278),
-        status: "Up",
-    },
-    Launcher {
-        name: "Europe-2",
-        location: "CDG",
-        coords: (48.8566, 2.3522),
-        status: "Down",
-    },
-    Launcher {
-        name: "Asia-2",
-        location: "HND",
-        coords: (35.6895, 139.6917),
-        status: "Up",
-    },
-    Launcher {
-        name: "Asia-3",
-        location: "ICN",
-        coords: (37.5665, 126.9780),
-        status: "Down",
-    },
-    Launcher {
-        name: "Africa-1",
-        location: "JNB",
-        coords: (-26.2041, 28.0473),
-        status: "Up",
-    },
-    Launcher {
-        name: "Africa-2",
-        location: "CAI",
-        coords: (30.0444, 31.2357),
-        status: "Down",
-    },
-    Launcher {
-        name: "Australia-1",
-        location: "SYD",
-        coords: (-33.8688, 151.2093),
-        status: "Up",
-    },
-    Launcher {
-        name: "SouthAmerica-1",
-        location: "GRU",
-        coords: (-23.5505, -46.6333),
-        status: "Up",
-    },
-    Launcher {
-        name: "Europe-3",
-        location: "FRA",
-        coords: (50.1109, 8.6821),
-        status: "Up",
-    },
-    Launcher {
-        name: "Europe-4",
-        location: "MAD",
-        coords: (40.4168, -3.7038),
-        status: "Up",
-    },
-    Launcher {
-        name: "Asia-4",
-        location: "PVG",
-        coords: (31.2304, 121.4737),
-        status: "Up",
-    },
-    Launcher {
-        name: "Asia-5",
-        location: "BOM",
-        coords: (19.0760, 72.8777),
-        status: "Down",
-    },
-    Launcher {
-        name: "Africa-3",
-        location: "LOS",
-        coords: (6.5244, 3.3792),
-        status: "Up",
-    },
-    Launcher {
-        name: "Africa-4",
-        location: "NBO",
-        coords: (-1.2864, 36.8172),
-        status: "Down",
-    },
-    Launcher {
-        name: "Australia-2",
-        location: "MEL",
-        coords: (-37.8136, 144.9631),
-        status: "Down",
-    },
-    Launcher {
-        name: "SouthAmerica-2",
-        location: "EZE",
-        coords: (-34.6037, -58.3816),
-        status: "Down",
-    },
-    Launcher {
-        name: "Europe-5",
-        location: "AMS",
-        coords: (52.3676, 4.9041),
-        status: "Up",
-    },
-    Launcher {
-        name: "Europe-6",
-        location: "FCO",
-        coords: (41.9028, 12.4964),
-        status: "Down",
-    },
-    Launcher {
-        name: "Asia-6",
-        location: "SIN",
-        coords: (1.3521, 103.8198),
-        status: "Up",
-    },
-    Launcher {
-        name: "Asia-7",
-        location: "BKK",
-        coords: (13.7563, 100.5018),
-        status: "Down",
-    },
-    Launcher {
-        name: "Africa-5",
-        location: "CPT",
-        coords: (-33.9249, 18.4241),
-        status: "Up",
-    },
-    Launcher {
-        name: "Africa-6",
-        location: "ALG",
-        coords: (36.7372, 3.0865),
-        status: "Up",
-    },
-    Launcher {
-        name: "Australia-3",
-        location: "BNE",
-        coords: (-27.4698, 153.0251),
-        status: "Up",
-    },
-    Launcher {
-        name: "SouthAmerica-3",
-        location: "LIM",
-        coords: (-12.0464, -77.0428),
-        status: "Down",
-    },
-];
+use serde::{Deserialize, Serialize};
+use std::{io, path::PathBuf, time::Duration};
+use tui::{layout::Rect, style::Color, widgets::ListState};
 
 #[derive(Clone)]
 pub struct RandomSignal {
     distribution: Uniform<u64>,
-    rng: ThreadRng,
+    rng: StdRng,
 }
 
 impl RandomSignal {
-    pub fn new(lower: u64, upper: u64) -> RandomSignal {
+    pub fn new(lower: u64, upper: u64, rng: StdRng) -> RandomSignal {
         RandomSignal {
             distribution: Uniform::new(lower, upper),
-            rng: rand::thread_rng(),
+            rng,
         }
     }
 }
@@ -402,45 +44,13 @@ impl Iterator for RandomSignal {
     }
 }
 
-#[derive(Clone)]
-pub struct CorruptedSinSignal {
-    x: f64,
-    interval: f64,
-    period: f64,
-    scale: f64,
-}
-
-impl CorruptedSinSignal {
-    pub fn new(interval: f64, period: f64, scale: f64) -> CorruptedSinSignal {
-        CorruptedSinSignal {
-            x: 0.0,
-            interval,
-            period,
-            scale,
-        }
-    }
-}
-
-impl Iterator for CorruptedSinSignal {
-    type Item = (f64, f64);
-    fn next(&mut self) -> Option<Self::Item> {
-        let point = (
-            self.x,
-            ((self.x * 1.0 / self.period).sin() + rand::thread_rng().gen_range(-0.1..0.1))
-                * self.scale,
-        );
-        self.x += self.interval;
-        Some(point)
-    }
-}
-
 pub struct TabsState<'a> {
     pub titles: Vec<&'a str>,
     pub index: usize,
 }
 
 impl<'a> TabsState<'a> {
-    pub fn new(titles: Vec<&'a str>) -> TabsState {
+    pub fn new(titles: Vec<&'a str>) -> TabsState<'a> {
         TabsState { titles, index: 0 }
     }
     pub fn next(&mut self) {
@@ -498,115 +108,822 @@ impl<T> StatefulList<T> {
     }
 }
 
+/// The last `window` points of `source`, `tick_rate` more every tick.
 pub struct Signal<S: Iterator> {
     source: S,
-    pub points: Vec<S::Item>,
+    points: RingBuffer<S::Item>,
     tick_rate: usize,
 }
 
 impl<S> Signal<S>
 where
     S: Iterator,
+    S::Item: Copy + Default,
 {
-    fn on_tick(&mut self) {
-        for _ in 0..self.tick_rate {
-            self.points.remove(0);
+    pub fn new(mut source: S, window: usize, tick_rate: usize) -> Signal<S> {
+        let mut points = RingBuffer::with_capacity(window);
+        points.extend(source.by_ref().take(window));
+        Signal {
+            source,
+            points,
+            tick_rate,
         }
+    }
+
+    pub fn points(&self) -> &[S::Item] {
+        self.points.as_slice()
+    }
+
+    pub fn on_tick(&mut self) {
         self.points
             .extend(self.source.by_ref().take(self.tick_rate));
     }
 }
 
+/// Ticks of signal shown on the chart.
+const SIGNAL_WINDOW: f64 = 20.0;
+
+/// A signal of the chart, as set up in the scenario.
+pub struct NamedSignal {
+    pub name: String,
+    pub marker: SignalMarker,
+    /// The theme's wave colors are used when `None`.
+    pub color: Option<Color>,
+    pub signal: Signal<Sampler>,
+}
+
+/// Share of the strength range added above and below the points when the
+/// strength axis is fitted to them.
+const SIGNAL_PADDING: f64 = 0.1;
+
 pub struct Signals {
-    pub sin1: Signal<CorruptedSinSignal>,
-    pub sin2: Signal<CorruptedSinSignal>,
-    pub window: [f64; 2],
+    pub list: Vec<NamedSignal>,
+    pub chart: ChartConfig,
 }
 
 impl Signals {
+    /// Each signal's random values are drawn from a generator seeded by
+    /// `rng`, in scenario order.
+    fn new(scenario: &Scenario, rng: &mut StdRng) -> Signals {
+        let list = scenario
+            .signals
+            .iter()
+            .map(|config| {
+                let source = signal::build(&config.source, StdRng::seed_from_u64(rng.gen()));
+                let sampler = Sampler::new(source, 1.0 / config.samples as f64);
+                NamedSignal {
+                    name: config.name.clone(),
+                    marker: config.marker,
+                    color: config.color.as_deref().and_then(parse_color),
+                    signal: Signal::new(
+                        sampler,
+                        config.samples * SIGNAL_WINDOW as usize,
+                        config.samples,
+                    ),
+                }
+            })
+            .collect();
+        Signals {
+            list,
+            chart: scenario.chart.clone(),
+        }
+    }
+
+    /// Time from the oldest to the newest point of any signal.
+    pub fn x_bounds(&self) -> [f64; 2] {
+        let mut bounds = [f64::INFINITY, f64::NEG_INFINITY];
+        for named in &self.list {
+            let points = named.signal.points();
+            if let (Some(first), Some(last)) = (points.first(), points.last()) {
+                bounds[0] = bounds[0].min(first.0);
+                bounds[1] = bounds[1].max(last.0);
+            }
+        }
+        if bounds[0] < bounds[1] {
+            bounds
+        } else {
+            [0.0, SIGNAL_WINDOW]
+        }
+    }
+
+    /// The scenario's strength bounds, or when auto-scaling, the range of the
+    /// points padded by `SIGNAL_PADDING` on both sides.
+    pub fn y_bounds(&self) -> [f64; 2] {
+        if !self.chart.auto_scale {
+            return self.chart.y_bounds;
+        }
+        let (low, high) = self
+            .list
+            .iter()
+            .flat_map(|named| named.signal.points())
+            .fold(
+                (f64::INFINITY, f64::NEG_INFINITY),
+                |(low, high), &(_, y)| (low.min(y), high.max(y)),
+            );
+        if low > high {
+            return self.chart.y_bounds;
+        }
+        // A flat line still gets some room around it.
+        let padding = ((high - low) * SIGNAL_PADDING).max(1.0);
+        [low - padding, high + padding]
+    }
+
     fn on_tick(&mut self) {
-        self.sin1.on_tick();
-        self.sin2.on_tick();
-        self.window[0] += 1.0;
-        self.window[1] += 1.0;
+        for named in &mut self.list {
+            named.signal.on_tick();
+        }
     }
 }
 
-#[derive(Clone)]
-pub struct Launcher<'a> {
-    pub name: &'a str,
-    pub location: &'a str,
+/// Lifecycle of a launcher site. `Up` and `Down` are accepted in scenario
+/// files for compatibility and map to `Ready` and `Offline`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LauncherState {
+    #[serde(alias = "Down")]
+    Offline,
+    Booting,
+    #[serde(alias = "Up")]
+    Ready,
+    Armed,
+    Launching,
+    Reloading,
+    Fault,
+}
+
+impl LauncherState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LauncherState::Offline => "Offline",
+            LauncherState::Booting => "Booting",
+            LauncherState::Ready => "Ready",
+            LauncherState::Armed => "Armed",
+            LauncherState::Launching => "Launching",
+            LauncherState::Reloading => "Reloading",
+            LauncherState::Fault => "Fault",
+        }
+    }
+
+    pub fn can_launch(&self) -> bool {
+        matches!(self, LauncherState::Ready | LauncherState::Armed)
+    }
+
+    /// Ticks until the launcher moves on by itself, and where it goes.
+    fn timeout(&self) -> Option<(u64, LauncherState)> {
+        match self {
+            LauncherState::Offline => Some((600, LauncherState::Booting)),
+            LauncherState::Booting => Some((50, LauncherState::Ready)),
+            LauncherState::Launching => Some((30, LauncherState::Reloading)),
+            LauncherState::Reloading => Some((150, LauncherState::Ready)),
+            LauncherState::Fault => Some((300, LauncherState::Offline)),
+            LauncherState::Ready | LauncherState::Armed => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Launcher {
+    pub name: String,
+    pub location: String,
     pub coords: (f64, f64),
-    pub status: &'a str,
+    #[serde(alias = "status")]
+    pub state: LauncherState,
+    /// Ticks spent in the current state.
+    #[serde(skip)]
+    pub ticks: u64,
+}
+
+impl Launcher {
+    pub fn set_state(&mut self, state: LauncherState) {
+        if self.state != state {
+            self.state = state;
+            self.ticks = 0;
+        }
+    }
+
+    /// Returns the state the launcher moved on from, if it timed out.
+    fn on_tick(&mut self) -> Option<LauncherState> {
+        self.ticks += 1;
+        let (ticks, next) = self.state.timeout()?;
+        if self.ticks < ticks {
+            return None;
+        }
+        let previous = self.state;
+        self.set_state(next);
+        Some(previous)
+    }
+
+    /// Whether `message` names the launcher's location as a word.
+    pub fn is_mentioned(&self, message: &str) -> bool {
+        message
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| word == self.location)
+    }
+
+    /// Work out how a system message about this launcher changes its state.
+    fn on_log(&mut self, message: &str, level: LogLevel) {
+        const FAULTS: [&str; 6] = [
+            "outage",
+            "failure",
+            "shutdown",
+            "Lost connection",
+            "malfunction",
+            "leak",
+        ];
+        const RECOVERIES: [&str; 4] = [
+            "ready and stable",
+            "rebooted successfully",
+            "initialization completed",
+            "reconfigured successfully",
+        ];
+        if !self.is_mentioned(message) || self.state == LauncherState::Launching {
+            return;
+        }
+        if level == LogLevel::Critical || FAULTS.iter().any(|fault| message.contains(fault)) {
+            self.set_state(LauncherState::Fault);
+        } else if message.contains("aborted") && self.state == LauncherState::Armed {
+            self.set_state(LauncherState::Ready);
+        } else if RECOVERIES.iter().any(|recovery| message.contains(recovery))
+            && matches!(self.state, LauncherState::Offline | LauncherState::Fault)
+        {
+            self.set_state(LauncherState::Booting);
+        }
+    }
+}
+
+/// Simulated time that passes on every call of `App::on_tick`, and by
+/// default the real time between calls.
+pub const TICK_RATE: Duration = Duration::from_millis(100);
+/// Simulation speeds the operator cycles through, as multiples of real time.
+pub const SPEEDS: [u32; 4] = [1, 2, 4, 8];
+
+/// Simulated seconds that pass on every tick while a missile is in flight.
+pub const SECONDS_PER_TICK: f64 = 10.0;
+/// Average missile speed in km/s.
+pub const MISSILE_SPEED: f64 = 7.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Launcher(usize),
+    Coords(f64, f64),
+}
+
+pub struct Missile {
+    pub target: String,
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub distance: f64,
+    pub ticks: u64,
+    pub flight_ticks: u64,
+}
+
+impl Missile {
+    pub fn new(target: String, from: (f64, f64), to: (f64, f64)) -> Missile {
+        let distance = geo::distance(from, to);
+        let flight_ticks = (distance / MISSILE_SPEED / SECONDS_PER_TICK)
+            .ceil()
+            .max(1.0) as u64;
+        Missile {
+            target,
+            from,
+            to,
+            distance,
+            ticks: 0,
+            flight_ticks,
+        }
+    }
+
+    /// Estimated flight time in simulated seconds.
+    pub fn flight_time(&self) -> f64 {
+        self.distance / MISSILE_SPEED
+    }
+
+    /// Simulated seconds since launch.
+    pub fn elapsed(&self) -> f64 {
+        (self.ticks as f64 * SECONDS_PER_TICK).min(self.flight_time())
+    }
+
+    pub fn fraction(&self) -> f64 {
+        (self.ticks as f64 / self.flight_ticks as f64).min(1.0)
+    }
+
+    pub fn position(&self) -> (f64, f64) {
+        geo::interpolate(self.from, self.to, self.fraction())
+    }
+
+    pub fn has_impacted(&self) -> bool {
+        self.ticks >= self.flight_ticks
+    }
+
+    /// Advance the missile, returns `true` on the tick it hits the target.
+    fn on_tick(&mut self) -> bool {
+        if self.has_impacted() {
+            return false;
+        }
+        self.ticks += 1;
+        self.has_impacted()
+    }
+}
+
+/// How long the countdown runs once every code is accepted.
+pub const COUNTDOWN: Duration = Duration::from_secs(10);
+
+/// Checks the launch sequence ticks off during the countdown, in order.
+pub const LAUNCH_STEPS: [&str; 3] = ["Fuel", "Guidance", "Arming"];
+
+/// The countdown between the last accepted code and lift-off, which can be
+/// aborted until T-0.
+pub struct LaunchSequence {
+    /// Index of the launching launcher.
+    pub source: usize,
+    pub target: String,
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub ticks: u64,
+    pub countdown_ticks: u64,
+}
+
+impl LaunchSequence {
+    /// Whole seconds left until T-0, rounded up.
+    pub fn remaining_secs(&self) -> u64 {
+        let ticks = self.countdown_ticks.saturating_sub(self.ticks);
+        (ticks * TICK_RATE.as_millis() as u64).div_ceil(1000)
+    }
+
+    /// How many of `LAUNCH_STEPS` are done. They split the countdown evenly,
+    /// the last one finishing ahead of T-0.
+    pub fn steps_done(&self) -> usize {
+        let steps = LAUNCH_STEPS.len() as u64;
+        (self.ticks * (steps + 1) / self.countdown_ticks).min(steps) as usize
+    }
+
+    /// Count down, returns `true` on reaching T-0.
+    fn on_tick(&mut self) -> bool {
+        self.ticks += 1;
+        self.ticks >= self.countdown_ticks
+    }
+}
+
+/// Where the clickable parts of the dashboard were drawn last frame,
+/// filled in by `ui::draw`.
+#[derive(Clone, Debug, Default)]
+pub struct Areas {
+    pub tabs: Vec<Rect>,
+    pub logs: Rect,
+    /// Rows of the Launchers table, below its header.
+    pub launchers: Rect,
+    /// Index of the launcher in the first visible row.
+    pub launchers_offset: usize,
+    /// Inside of the World Map canvas, spanning -180..180 and -90..90.
+    pub map: Rect,
 }
 
 pub struct App<'a> {
     pub title: &'a str,
     pub should_quit: bool,
     pub tabs: TabsState<'a>,
-    pub progress: f64,
     pub sparkline: Signal<RandomSignal>,
-    pub tasks: StatefulList<&'a str>,
-    pub logs: StatefulList<(&'a str, &'a str)>,
+    /// Items to check before the launch panel opens.
+    pub checklist: StatefulList<ChecklistItem>,
+    /// Every message of the session, oldest first. The System Message panel
+    /// only draws the newest ones that fit.
+    pub logs: StatefulList<LogEntry>,
+    /// Messages the simulation picks from.
+    log_templates: Vec<LogMessage>,
+    /// Where the log is written on exit and with the export key.
+    pub log_export: Option<PathBuf>,
+    /// Every operator action is appended to this trail.
+    pub audit: Option<AuditLog>,
+    /// Up and down step through the System Message panel instead of the
+    /// checklist.
+    pub logs_focused: bool,
+    /// The selected message stays put instead of the panel following the
+    /// newest one.
+    pub logs_paused: bool,
+    /// Least severe level shown in the System Message panel.
+    pub log_filter: Option<LogLevel>,
+    pub log_search: String,
+    pub searching: bool,
     pub signals: Signals,
-    pub packets: Vec<(&'a str, u64)>,
-    pub launchers: Vec<Launcher<'a>>,
+    /// Packets sent by each launcher.
+    pub traffic: Traffic,
+    /// Events of the scenario still to come.
+    pub timeline: Timeline,
+    /// From `DEFCON_PEACE` down to 1, set by the timeline.
+    pub defcon: u8,
+    pub launchers: StatefulList<Launcher>,
     pub power: f64,
     pub code: String,
-    pub correct_code: String,
+    pub authorization: Authorization,
+    /// Outcome of the last submitted code, `None` until <Enter> is pressed.
+    pub code_accepted: Option<bool>,
     pub typing: bool,
     pub missile_launched: bool,
+    pub source: Option<usize>,
+    pub target: Option<Target>,
+    pub target_input: String,
+    pub entering_target: bool,
+    pub missile: Option<Missile>,
+    /// Runs from the last accepted code until T-0 or an abort.
+    pub sequence: Option<LaunchSequence>,
+    /// Length of the launch countdown.
+    pub countdown: Duration,
+    pub keymap: Keymap,
+    pub theme: Theme,
+    pub areas: Areas,
+    pub seed: u64,
+    /// Ticks since the session started.
+    pub ticks: u64,
+    /// The simulation stops ticking, though the dashboard still takes input.
+    pub paused: bool,
+    /// One of `SPEEDS`.
+    pub speed: u32,
+    /// Real time a tick takes at 1x speed. The authorization countdowns run
+    /// by it rather than by simulated time.
+    pub tick_rate: Duration,
+    rng: StdRng,
 }
 
 impl<'a> App<'a> {
-    pub fn new(title: &'a str, correct_code: String) -> App<'a> {
-        let mut rand_signal = RandomSignal::new(0, 100);
-        let sparkline_points = rand_signal.by_ref().take(300).collect();
-        let mut sin_signal = CorruptedSinSignal::new(0.2, 3.0, 16.0);
-        let sin1_points = sin_signal.by_ref().take(100).collect();
-        let mut sin_signal2 = CorruptedSinSignal::new(0.1, 2.0, 8.0);
-        let sin2_points = sin_signal2.by_ref().take(200).collect();
-        App {
+    /// Every random value in the session is derived from `seed`, so the same
+    /// seed and input always give the same dashboard.
+    pub fn new(
+        title: &'a str,
+        authorization: Authorization,
+        scenario: &Scenario,
+        seed: u64,
+    ) -> App<'a> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rand_signal = RandomSignal::new(0, 100, StdRng::seed_from_u64(rng.gen()));
+        let signals = Signals::new(scenario, &mut rng);
+        let mut app = App {
             title,
             should_quit: false,
             tabs: TabsState::new(vec!["System Monitor", "Launch Missile"]),
-            progress: 0.0,
-            sparkline: Signal {
-                source: rand_signal,
-                points: sparkline_points,
-                tick_rate: 1,
-            },
-            tasks: StatefulList::with_items(TASKS.to_vec()),
-            logs: StatefulList::with_items(LOGS.to_vec()),
-            signals: Signals {
-                sin1: Signal {
-                    source: sin_signal,
-                    points: sin1_points,
-                    tick_rate: 5,
-                },
-                sin2: Signal {
-                    source: sin_signal2,
-                    points: sin2_points,
-                    tick_rate: 10,
-                },
-                window: [0.0, 20.0],
-            },
-            packets: EVENTS.to_vec(),
-            launchers: LAUNCHERS.to_vec(),
+            sparkline: Signal::new(rand_signal, 300, 1),
+            checklist: StatefulList::with_items(scenario.checklist.clone()),
+            logs: StatefulList::with_items(Vec::new()),
+            log_templates: scenario.logs.clone(),
+            log_export: None,
+            audit: None,
+            logs_focused: false,
+            logs_paused: false,
+            log_filter: None,
+            log_search: String::new(),
+            searching: false,
+            signals,
+            timeline: Timeline::new(&scenario.timeline),
+            defcon: DEFCON_PEACE,
+            traffic: Traffic::new(
+                &scenario.launchers,
+                &scenario.packets,
+                StdRng::seed_from_u64(rng.gen()),
+            ),
+            launchers: StatefulList::with_items(scenario.launchers.clone()),
             power: 50.0,
             code: String::new(),
             typing: false,
             missile_launched: false,
-            correct_code,
+            authorization,
+            code_accepted: None,
+            source: None,
+            target: None,
+            target_input: String::new(),
+            entering_target: false,
+            missile: None,
+            sequence: None,
+            countdown: COUNTDOWN,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            areas: Areas::default(),
+            seed,
+            ticks: 0,
+            paused: false,
+            speed: SPEEDS[0],
+            tick_rate: TICK_RATE,
+            rng,
+        };
+        app.log(format!("Simulation seed is {}", seed), LogLevel::Info);
+        app
+    }
+
+    pub fn source_launcher(&self) -> Option<&Launcher> {
+        self.source.map(|i| &self.launchers.items[i])
+    }
+
+    pub fn target_coords(&self) -> Option<(f64, f64)> {
+        match self.target? {
+            Target::Launcher(i) => Some(self.launchers.items[i].coords),
+            Target::Coords(lat, lon) => Some((lat, lon)),
+        }
+    }
+
+    pub fn target_name(&self) -> Option<String> {
+        match self.target? {
+            Target::Launcher(i) => Some(self.launchers.items[i].name.clone()),
+            Target::Coords(lat, lon) => Some(format!("{:.2}, {:.2}", lat, lon)),
+        }
+    }
+
+    /// Source and target coordinates, once both have been picked.
+    pub fn trajectory(&self) -> Option<((f64, f64), (f64, f64))> {
+        Some((self.source_launcher()?.coords, self.target_coords()?))
+    }
+
+    /// Simulated seconds since the session started.
+    pub fn time(&self) -> f64 {
+        self.ticks as f64 * TICK_RATE.as_millis() as f64 / 1000.0
+    }
+
+    /// Append an operator action to the audit trail, if there is one.
+    fn audit(&mut self, action: &str, outcome: Outcome, detail: Option<String>) {
+        let time = TICK_RATE * self.ticks as u32;
+        if let Some(audit) = &mut self.audit {
+            if let Err(err) = audit.append(time, action, outcome, detail) {
+                self.log(
+                    format!("Audit trail write failed: {}", err),
+                    LogLevel::Error,
+                );
+            }
+        }
+    }
+
+    /// Put a message at the top of the System Message panel.
+    /// The source is the first launcher whose location the message names.
+    fn log(&mut self, message: String, level: LogLevel) {
+        let source = self
+            .launchers
+            .items
+            .iter()
+            .find(|launcher| launcher.is_mentioned(&message))
+            .map(|launcher| launcher.name.clone());
+        self.log_from(source, message, level);
+    }
+
+    fn log_from(&mut self, source: Option<String>, message: String, level: LogLevel) {
+        let entry = LogEntry {
+            time: self.time(),
+            level,
+            source,
+            message,
+        };
+        for launcher in &mut self.launchers.items {
+            launcher.on_log(&entry.message, entry.level);
+        }
+        // Alerts make the traffic of the launchers they are about burst,
+        // or of every launcher when critical and about none in particular.
+        if entry.level >= LogLevel::Warning {
+            let mut about = self
+                .launchers
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(_, launcher)| {
+                    launcher.is_mentioned(&entry.message)
+                        || entry.source.as_ref() == Some(&launcher.name)
+                })
+                .map(|(i, _)| i)
+                .peekable();
+            if about.peek().is_none() {
+                if entry.level == LogLevel::Critical {
+                    self.traffic.alert_all();
+                }
+            } else {
+                for i in about {
+                    self.traffic.alert(i);
+                }
+            }
+        }
+        self.logs.items.push(entry);
+    }
+
+    /// Whether message `i` passes `log_filter`.
+    pub fn shows_log(&self, i: usize) -> bool {
+        self.logs.items[i].level >= self.log_filter.unwrap_or(LogLevel::Info)
+    }
+
+    /// Indices of the messages that pass `log_filter`, newest first.
+    pub fn visible_logs(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.logs.items.len())
+            .rev()
+            .filter(|&i| self.shows_log(i))
+    }
+
+    /// Move the selection to the next older or newer visible message, pausing
+    /// the panel. It stays on the oldest or newest one once there.
+    pub fn step_logs(&mut self, forward: bool) {
+        let next = match self.logs.state.selected() {
+            Some(i) if forward => (0..i).rev().find(|&j| self.shows_log(j)).or(Some(i)),
+            Some(i) => (i + 1..self.logs.items.len())
+                .find(|&j| self.shows_log(j))
+                .or(Some(i)),
+            None => self.visible_logs().next(),
+        };
+        if next.is_some() {
+            self.logs.state.select(next);
+            self.logs_paused = true;
+        }
+    }
+
+    fn pause_logs(&mut self, paused: bool) {
+        self.logs_paused = paused;
+        if paused {
+            if self.logs.state.selected().is_none() {
+                self.step_logs(true);
+            }
+        } else {
+            self.logs.state = ListState::default();
+        }
+    }
+
+    /// Show every message, then only warnings and above, errors and above,
+    /// critical ones, and round again.
+    fn cycle_log_filter(&mut self) {
+        self.log_filter = match self.log_filter {
+            None => Some(LogLevel::Warning),
+            Some(LogLevel::Info) | Some(LogLevel::Warning) => Some(LogLevel::Error),
+            Some(LogLevel::Error) => Some(LogLevel::Critical),
+            Some(LogLevel::Critical) => None,
+        };
+        if let Some(i) = self.logs.state.selected() {
+            if !self.shows_log(i) {
+                let newest = self.visible_logs().next();
+                self.logs.state.select(newest);
+            }
+        }
+    }
+
+    /// Write the session's log oldest first to `log_export`, or to
+    /// `missile-launcher-<seed>.jsonl` without one.
+    pub fn export_logs(&self) -> io::Result<(PathBuf, usize)> {
+        let path = self
+            .log_export
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("missile-launcher-{}.jsonl", self.seed)));
+        let count = logs::export(&path, &self.logs.items)?;
+        Ok((path, count))
+    }
+
+    /// Great-circle distance of the planned trajectory in kilometres.
+    pub fn flight_distance(&self) -> Option<f64> {
+        let (from, to) = self.trajectory()?;
+        Some(geo::distance(from, to))
+    }
+
+    /// The mandatory checklist items still unchecked, comma separated.
+    pub fn open_checklist_items(&self) -> Option<String> {
+        let open = self
+            .checklist
+            .items
+            .iter()
+            .filter(|item| item.mandatory && !item.checked)
+            .map(|item| item.label.as_str())
+            .collect::<Vec<_>>();
+        (!open.is_empty()).then(|| open.join(", "))
+    }
+
+    /// Start the countdown once every code is accepted.
+    fn launch(&mut self) {
+        // The codes are spent whatever comes of them.
+        self.authorization.reset();
+        self.typing = false;
+        self.code_accepted = None;
+        let Some((from, to)) = self.trajectory() else {
+            let message = "Launch refused, no source and target picked".to_string();
+            self.audit("launch", Outcome::Refused, Some(message.clone()));
+            self.log(message, LogLevel::Warning);
+            return;
+        };
+        let launcher = self.source_launcher().unwrap();
+        let source = launcher.name.clone();
+        if !launcher.state.can_launch() {
+            let message = format!("{} cannot launch while {}", source, launcher.state.as_str());
+            self.audit("launch", Outcome::Refused, Some(message.clone()));
+            self.log_from(Some(source), message, LogLevel::Warning);
+            return;
+        }
+        let sequence = LaunchSequence {
+            source: self.source.unwrap(),
+            target: self.target_name().unwrap(),
+            from,
+            to,
+            ticks: 0,
+            countdown_ticks: (self.countdown.as_millis() / TICK_RATE.as_millis()).max(1) as u64,
+        };
+        let message = format!(
+            "Launch sequence started from {} towards {}, T-{}s",
+            source,
+            sequence.target,
+            sequence.remaining_secs()
+        );
+        self.audit("launch", Outcome::Accepted, Some(message.clone()));
+        self.log_from(Some(source), message, LogLevel::Critical);
+        self.sequence = Some(sequence);
+        self.clear_impact();
+    }
+
+    /// Take a missile that has hit its target off the map, once a new
+    /// trajectory is picked or a new launch starts.
+    fn clear_impact(&mut self) {
+        if self.missile.as_ref().is_some_and(Missile::has_impacted) {
+            self.missile = None;
+        }
+    }
+
+    /// Stop the countdown, the launcher stays armed.
+    fn abort(&mut self) -> (Outcome, Option<String>) {
+        let Some(sequence) = self.sequence.take() else {
+            return (Outcome::Ignored, None);
+        };
+        let source = self.launchers.items[sequence.source].name.clone();
+        let at = format!("T-{}s", sequence.remaining_secs());
+        let message = format!("Launch from {} aborted at {}", source, at);
+        self.log_from(Some(source), message, LogLevel::Warning);
+        (Outcome::Done, Some(at))
+    }
+
+    /// Fire at T-0, unless the launcher broke down during the countdown.
+    fn lift_off(&mut self) {
+        let Some(sequence) = self.sequence.take() else {
+            return;
+        };
+        let launcher = &mut self.launchers.items[sequence.source];
+        let source = launcher.name.clone();
+        if !launcher.state.can_launch() {
+            let message = format!(
+                "Launch from {} aborted at T-0, launcher is {}",
+                source,
+                launcher.state.as_str()
+            );
+            self.audit("launch", Outcome::Refused, Some(message.clone()));
+            self.log_from(Some(source), message, LogLevel::Error);
+            return;
+        }
+        launcher.set_state(LauncherState::Launching);
+        let message = format!(
+            "Missile launched from {} towards {}",
+            source, sequence.target
+        );
+        self.audit("launch", Outcome::Launched, Some(message.clone()));
+        self.log_from(Some(source), message, LogLevel::Critical);
+        self.missile = Some(Missile::new(sequence.target, sequence.from, sequence.to));
+        self.missile_launched = true;
+        // The next launch goes through the checklist again.
+        for item in &mut self.checklist.items {
+            item.checked = false;
+        }
+    }
+
+    /// Route a key press to the action it is bound to. While a popup takes
+    /// text, its own actions come first and unbound characters are typed in.
+    pub fn handle_key(&mut self, key: Key) {
+        let actions = self.keymap.actions(key);
+        let text = self.typing || self.entering_target || self.searching;
+        // The abort key beats whatever else it is bound to during a
+        // countdown, but not text typed into a popup.
+        if self.sequence.is_some() && !text && actions.contains(&Action::Abort) {
+            return self.on_action(Action::Abort);
+        }
+        if text {
+            if let Some(&action) = actions.iter().find(|action| action.is_text()) {
+                return self.on_action(action);
+            }
+            if let KeyCode::Char(c) = key.code {
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                {
+                    return self.on_char(c);
+                }
+            }
+        }
+        if let Some(&action) = actions.iter().find(|action| !action.is_text()) {
+            self.on_action(action);
         }
     }
 
     pub fn on_up(&mut self) {
-        self.tasks.previous();
+        if self.tabs.index == 1 {
+            self.launchers.previous();
+        } else if self.logs_focused {
+            self.step_logs(false);
+        } else if !self.checklist.items.is_empty() {
+            self.checklist.previous();
+        }
     }
 
     pub fn on_down(&mut self) {
-        self.tasks.next();
+        if self.tabs.index == 1 {
+            self.launchers.next();
+        } else if self.logs_focused {
+            self.step_logs(true);
+        } else if !self.checklist.items.is_empty() {
+            self.checklist.next();
+        }
+    }
+
+    fn tab_title(&self) -> String {
+        self.tabs.titles[self.tabs.index].to_string()
     }
 
     pub fn on_right(&mut self) {
@@ -617,64 +934,636 @@ impl<'a> App<'a> {
         self.tabs.previous();
     }
 
-    pub fn on_key(&mut self, c: KeyCode) {
-        if self.typing {
-            match c {
-                KeyCode::Enter => {
-                    if self.code == self.correct_code {
-                        self.missile_launched = true;
-                        self.typing = false;
+    /// Clicks switch tabs, select launchers and pick map targets, the wheel
+    /// scrolls the System Message panel. Ignored while a popup is open.
+    pub fn on_mouse(&mut self, event: MouseEvent) {
+        if self.typing || self.entering_target {
+            return;
+        }
+        let (column, row) = (event.column, event.row);
+        let hit = |area: &Rect| {
+            (area.left()..area.right()).contains(&column)
+                && (area.top()..area.bottom()).contains(&row)
+        };
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(i) = self.areas.tabs.iter().position(hit) {
+                    self.tabs.index = i;
+                    self.audit("click_tab", Outcome::Done, Some(self.tab_title()));
+                } else if self.tabs.index == 1 && hit(&self.areas.launchers) {
+                    let i = self.areas.launchers_offset + (row - self.areas.launchers.y) as usize;
+                    if i < self.launchers.items.len() {
+                        self.launchers.state.select(Some(i));
+                        let name = self.launchers.items[i].name.clone();
+                        self.audit("click_launcher", Outcome::Done, Some(name));
                     }
+                } else if self.tabs.index == 1 && hit(&self.areas.map) {
+                    let map = self.areas.map;
+                    let x = (column - map.x) as f64 + 0.5;
+                    let y = (row - map.y) as f64 + 0.5;
+                    let lon = x / map.width as f64 * 360.0 - 180.0;
+                    let lat = 90.0 - y / map.height as f64 * 180.0;
+                    self.target = Some(Target::Coords(lat, lon));
+                    self.clear_impact();
+                    self.audit("click_map", Outcome::Done, self.target_name());
+                }
+            }
+            MouseEventKind::ScrollDown if self.tabs.index == 0 && hit(&self.areas.logs) => {
+                self.step_logs(true);
+                self.audit("scroll_logs", Outcome::Done, Some("down".to_string()));
+            }
+            MouseEventKind::ScrollUp if self.tabs.index == 0 && hit(&self.areas.logs) => {
+                self.step_logs(false);
+                self.audit("scroll_logs", Outcome::Done, Some("up".to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    fn on_char(&mut self, c: char) {
+        if self.searching {
+            self.log_search.push(c);
+        } else if self.entering_target {
+            self.target_input.push(c);
+        } else if self.typing {
+            self.code.push(c);
+            self.code_accepted = None;
+        }
+    }
+
+    /// Carry out `action` and append it to the audit trail with its outcome.
+    pub fn on_action(&mut self, action: Action) {
+        let (outcome, detail) = match action {
+            Action::NextTab => {
+                self.on_right();
+                (Outcome::Done, Some(self.tab_title()))
+            }
+            Action::PrevTab => {
+                self.on_left();
+                (Outcome::Done, Some(self.tab_title()))
+            }
+            Action::Up => {
+                self.on_up();
+                (Outcome::Done, None)
+            }
+            Action::Down => {
+                self.on_down();
+                (Outcome::Done, None)
+            }
+            Action::Abort => self.abort(),
+            // Pausing or stepping would stop or skip ahead the real time the
+            // lockout and the confirmation window count.
+            Action::PauseSimulation | Action::StepSimulation
+                if !(action == Action::PauseSimulation && self.paused)
+                    && self.authorization.is_counting() =>
+            {
+                let message = "Simulation keeps running while the launch panel counts down";
+                self.log(message.to_string(), LogLevel::Warning);
+                (Outcome::Refused, None)
+            }
+            Action::PauseSimulation => {
+                self.paused = !self.paused;
+                let state = if self.paused { "paused" } else { "resumed" };
+                (Outcome::Done, Some(state.to_string()))
+            }
+            Action::StepSimulation => {
+                self.paused = true;
+                self.on_tick();
+                (Outcome::Done, Some(format!("tick {}", self.ticks)))
+            }
+            Action::CycleSpeed => {
+                let i = SPEEDS.iter().position(|&speed| speed == self.speed);
+                self.speed = SPEEDS[i.map_or(0, |i| (i + 1) % SPEEDS.len())];
+                (Outcome::Done, Some(format!("{}x", self.speed)))
+            }
+            _ if self.entering_target => return self.on_target_action(action),
+            _ if self.typing => return self.on_code_action(action),
+            _ if self.searching => return self.on_search_action(action),
+            Action::Quit => {
+                self.should_quit = true;
+                (Outcome::Done, None)
+            }
+            Action::OpenLaunchPanel => {
+                if self.authorization.is_locked() {
+                    self.log("Launch panel is locked".to_string(), LogLevel::Warning);
+                    (Outcome::LockedOut, None)
+                } else if self.sequence.is_some() {
+                    let message = "Launch sequence already running".to_string();
+                    self.log(message, LogLevel::Warning);
+                    (Outcome::Ignored, None)
+                } else if self.trajectory().is_none() {
+                    let message =
+                        "Launch panel refused, pick a source and a target first".to_string();
+                    self.log(message, LogLevel::Warning);
+                    (Outcome::Refused, None)
+                } else if let Some(open) = self.open_checklist_items() {
+                    let message = format!("Launch panel refused, checklist incomplete: {}", open);
+                    self.log(message, LogLevel::Warning);
+                    (Outcome::Refused, Some(open))
+                } else {
+                    self.typing = true;
+                    (Outcome::Done, None)
                 }
-                KeyCode::Backspace | KeyCode::Delete => {
-                    self.code.pop();
+            }
+            Action::SetSource if self.tabs.index == 1 => match self.launchers.state.selected() {
+                Some(i) => {
+                    if let Some(previous) = self.source {
+                        let launcher = &mut self.launchers.items[previous];
+                        if launcher.state == LauncherState::Armed {
+                            launcher.set_state(LauncherState::Ready);
+                        }
+                    }
+                    let launcher = &mut self.launchers.items[i];
+                    if launcher.state == LauncherState::Ready {
+                        launcher.set_state(LauncherState::Armed);
+                    }
+                    self.source = Some(i);
+                    self.clear_impact();
+                    (Outcome::Done, Some(self.launchers.items[i].name.clone()))
                 }
-                KeyCode::Esc => {
-                    self.code.clear();
-                    self.typing = false;
+                None => (Outcome::Ignored, None),
+            },
+            Action::SetTarget if self.tabs.index == 1 => match self.launchers.state.selected() {
+                Some(i) => {
+                    self.target = Some(Target::Launcher(i));
+                    self.clear_impact();
+                    (Outcome::Done, self.target_name())
                 }
-                KeyCode::Char(c) => {
-                    self.code.push(c);
+                None => (Outcome::Ignored, None),
+            },
+            Action::EnterCoords if self.tabs.index == 1 => {
+                self.entering_target = true;
+                (Outcome::Done, None)
+            }
+            Action::FocusLogs if self.tabs.index == 0 => {
+                self.logs_focused = !self.logs_focused;
+                (Outcome::Done, None)
+            }
+            Action::PauseLogs if self.tabs.index == 0 => {
+                self.pause_logs(!self.logs_paused);
+                (Outcome::Done, None)
+            }
+            Action::FilterLogs if self.tabs.index == 0 => {
+                self.cycle_log_filter();
+                let filter = self.log_filter.map(|level| level.as_str().to_string());
+                (Outcome::Done, filter)
+            }
+            Action::ToggleItem if self.tabs.index == 0 => match self.checklist.state.selected() {
+                Some(i) if i < self.checklist.items.len() => {
+                    let item = &mut self.checklist.items[i];
+                    item.checked = !item.checked;
+                    let detail = format!(
+                        "{}: {}",
+                        item.label,
+                        if item.checked { "checked" } else { "unchecked" }
+                    );
+                    (Outcome::Done, Some(detail))
                 }
-                _ => {}
+                _ => (Outcome::Ignored, None),
+            },
+            Action::SearchLogs if self.tabs.index == 0 => {
+                self.searching = true;
+                (Outcome::Done, None)
             }
-        } else {
-            match c {
-                KeyCode::Char('q') => {
-                    self.should_quit = true;
+            Action::ExportLogs => match self.export_logs() {
+                Ok((path, count)) => {
+                    let message = format!("Exported {} messages to {}", count, path.display());
+                    self.log(message, LogLevel::Info);
+                    (Outcome::Done, Some(path.display().to_string()))
                 }
-                KeyCode::Char('t') => {
-                    self.typing = true;
+                Err(err) => {
+                    let message = format!("Log export failed: {}", err);
+                    self.log(message.clone(), LogLevel::Error);
+                    (Outcome::Failed, Some(message))
                 }
-                _ => {}
+            },
+            _ => (Outcome::Ignored, None),
+        };
+        self.audit(action.as_str(), outcome, detail);
+    }
+
+    fn on_search_action(&mut self, action: Action) {
+        match action {
+            Action::Submit => {
+                self.searching = false;
+                let query = Some(self.log_search.clone());
+                self.audit("submit_search", Outcome::Done, query);
+            }
+            Action::Delete => {
+                self.log_search.pop();
+            }
+            Action::Cancel => {
+                self.log_search.clear();
+                self.searching = false;
+                self.audit("clear_search", Outcome::Done, None);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_target_action(&mut self, action: Action) {
+        match action {
+            Action::Submit => {
+                let input = self.target_input.clone();
+                match geo::parse_coords(&input) {
+                    Some((lat, lon)) => {
+                        self.target = Some(Target::Coords(lat, lon));
+                        self.clear_impact();
+                        self.target_input.clear();
+                        self.entering_target = false;
+                        self.audit("submit_coords", Outcome::Accepted, self.target_name());
+                    }
+                    None => self.audit("submit_coords", Outcome::Rejected, Some(input)),
+                }
+            }
+            Action::Delete => {
+                self.target_input.pop();
+            }
+            Action::Cancel => {
+                self.target_input.clear();
+                self.entering_target = false;
+                self.audit("cancel_coords", Outcome::Done, None);
+            }
+            _ => {}
+        }
+    }
+
+    /// The code itself never makes it into the audit trail, only which
+    /// operator's turn it was.
+    fn on_code_action(&mut self, action: Action) {
+        match action {
+            Action::Submit => {
+                let operator = format!(
+                    "operator {}/{}",
+                    self.authorization.accepted + 1,
+                    self.authorization.codes.len()
+                );
+                let attempt = self.authorization.submit(&self.code);
+                self.code.clear();
+                self.code_accepted = Some(attempt == Attempt::Accepted);
+                match attempt {
+                    Attempt::Accepted => {
+                        self.audit("submit_code", Outcome::Accepted, Some(operator));
+                        self.log(
+                            format!(
+                                "Launch authorization {}/{} accepted",
+                                self.authorization.accepted,
+                                self.authorization.codes.len()
+                            ),
+                            LogLevel::Info,
+                        )
+                    }
+                    Attempt::Rejected => {
+                        self.audit("submit_code", Outcome::Rejected, Some(operator));
+                        self.log(
+                            format!(
+                                "Invalid launch code entered ({}/{} attempts)",
+                                self.authorization.failures, self.authorization.max_failures
+                            ),
+                            LogLevel::Warning,
+                        )
+                    }
+                    Attempt::Reused => {
+                        self.audit("submit_code", Outcome::Rejected, Some(operator));
+                        self.log(
+                            "Launch code already entered by an earlier operator".to_string(),
+                            LogLevel::Warning,
+                        )
+                    }
+                    Attempt::NotPending => {
+                        self.audit("submit_code", Outcome::Ignored, Some(operator));
+                        self.code_accepted = None;
+                    }
+                    Attempt::LockedOut(cooldown) => {
+                        self.audit("submit_code", Outcome::LockedOut, Some(operator));
+                        self.log(
+                            format!(
+                                "Launch panel locked for {}s after repeated invalid codes",
+                                cooldown.as_secs()
+                            ),
+                            LogLevel::Critical,
+                        );
+                        self.code_accepted = None;
+                        self.typing = false;
+                    }
+                }
+                if self.authorization.is_complete() {
+                    self.launch();
+                }
+            }
+            Action::Delete => {
+                self.code.pop();
+                self.code_accepted = None;
+            }
+            Action::Cancel => {
+                self.code.clear();
+                self.code_accepted = None;
+                self.typing = false;
+                self.audit("close_launch_panel", Outcome::Done, None);
+            }
+            _ => {}
+        }
+    }
+
+    /// Carry out a timeline event and note it in the audit trail, so what the
+    /// trainees saw can be told apart from what they did.
+    fn fire(&mut self, event: TimelineEvent) {
+        let mut done = Vec::new();
+        if let (Some(name), Some(state)) = (&event.launcher, event.state) {
+            for launcher in &mut self.launchers.items {
+                if launcher.name == *name || launcher.location == *name {
+                    launcher.set_state(state);
+                }
+            }
+            done.push(format!("{} {}", name, state.as_str()));
+        }
+        if let Some(log) = event.log {
+            done.push(format!("log {}", log.level.as_str()));
+            self.log(log.message, log.level);
+        }
+        if let Some(defcon) = event.defcon {
+            self.defcon = defcon;
+            done.push(format!("DEFCON {}", defcon));
+        }
+        let detail = format!("{}: {}", event.at, done.join(", "));
+        self.audit("timeline", Outcome::Done, Some(detail));
+    }
+
+    /// Report the launchers that timed out of a state, given with the state
+    /// they left. Launchers making the same move together share a message.
+    fn log_moves(&mut self, moved: Vec<(usize, LauncherState)>) {
+        let mut moves: Vec<(LauncherState, LauncherState, Vec<usize>)> = Vec::new();
+        for (i, previous) in moved {
+            let state = self.launchers.items[i].state;
+            match moves
+                .iter_mut()
+                .find(|(from, to, _)| (*from, *to) == (previous, state))
+            {
+                Some((_, _, launchers)) => launchers.push(i),
+                None => moves.push((previous, state, vec![i])),
+            }
+        }
+        for (from, to, launchers) in moves {
+            let (source, who) = match launchers[..] {
+                [i] => {
+                    let launcher = &self.launchers.items[i];
+                    (
+                        Some(launcher.name.clone()),
+                        format!("{} launcher", launcher.location),
+                    )
+                }
+                _ => (None, format!("{} launchers", launchers.len())),
+            };
+            let message = format!("{} went from {} to {}", who, from.as_str(), to.as_str());
+            let level = match to {
+                LauncherState::Offline => LogLevel::Warning,
+                _ => LogLevel::Info,
+            };
+            self.log_from(source, message, level);
+        }
+    }
+
+    /// Report the launchers whose traffic started to spike, in one message.
+    fn log_spikes(&mut self, spikes: Vec<usize>) {
+        let window = (TICK_RATE * traffic::WINDOW as u32).as_secs();
+        match spikes[..] {
+            [] => {}
+            [i] => {
+                let launcher = &self.launchers.items[i];
+                let message = format!(
+                    "Traffic spike from {}: {} packets in {} s",
+                    launcher.location,
+                    self.traffic.totals()[i],
+                    window
+                );
+                self.log_from(Some(launcher.name.clone()), message, LogLevel::Info);
+            }
+            _ => {
+                let message = format!(
+                    "Traffic spike from {} sites over the last {} s",
+                    spikes.len(),
+                    window
+                );
+                self.log_from(None, message, LogLevel::Info);
             }
         }
     }
 
     pub fn on_tick(&mut self) {
-        // Update progress
-        self.progress += 0.001;
-        if self.progress > 1.0 {
-            self.progress = 0.0;
+        self.ticks += 1;
+
+        if let Some(sequence) = &mut self.sequence {
+            let done = sequence.steps_done();
+            let lift_off = sequence.on_tick();
+            if sequence.steps_done() > done {
+                let source = self.launchers.items[sequence.source].name.clone();
+                let message = format!("{} check complete", LAUNCH_STEPS[done]);
+                self.log_from(Some(source), message, LogLevel::Info);
+            }
+            if lift_off {
+                self.lift_off();
+            }
         }
 
         self.sparkline.on_tick();
         self.signals.on_tick();
 
-        if (self.progress * 1000.0) as i64 % 5 == 0 {
-            let log = self.logs.items.pop().unwrap();
-            self.logs.items.insert(0, log);
+        let moved = self
+            .launchers
+            .items
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, launcher)| Some((i, launcher.on_tick()?)))
+            .collect();
+        self.log_moves(moved);
+
+        for event in self.timeline.due(TICK_RATE * self.ticks as u32) {
+            self.fire(event);
+        }
+
+        if self.ticks.is_multiple_of(5) {
+            let template = &self.log_templates[self.rng.gen_range(0..self.log_templates.len())];
+            self.log(template.message.clone(), template.level);
+        }
+
+        let spikes = self.traffic.on_tick(&self.launchers.items, TICK_RATE);
+        self.log_spikes(spikes);
+
+        match self.authorization.on_tick(self.tick_rate / self.speed) {
+            Some(Expired::Window) => {
+                self.code.clear();
+                self.log(
+                    "Launch authorization timed out".to_string(),
+                    LogLevel::Warning,
+                );
+            }
+            Some(Expired::Lockout) => {
+                self.log("Launch panel unlocked".to_string(), LogLevel::Info);
+            }
+            None => {}
+        }
+
+        if let Some(missile) = &mut self.missile {
+            if missile.on_tick() {
+                let message = format!("Impact confirmed at {}", missile.target);
+                self.log(message, LogLevel::Critical);
+            }
+        }
+
+        if self.ticks.is_multiple_of(10) {
+            self.power = (self.power + self.rng.gen::<f64>() * 50.0 - 25.0).clamp(0.0, 100.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launcher(state: LauncherState) -> Launcher {
+        Launcher {
+            name: "USA-1".to_string(),
+            location: "LAX".to_string(),
+            coords: (34.0, -118.2),
+            state,
+            ticks: 0,
         }
+    }
 
-        if (self.progress * 1000.0) as i64 % 3 == 0 {
-            let event = self.packets.pop().unwrap();
-            self.packets.insert(0, event);
+    /// Tick `launcher` until it moves on by itself, at most `limit` times.
+    fn ticks_to_move(launcher: &mut Launcher, limit: u64) -> Option<u64> {
+        let state = launcher.state;
+        for tick in 1..=limit {
+            if let Some(previous) = launcher.on_tick() {
+                assert_eq!(previous, state);
+                return Some(tick);
+            }
         }
+        None
+    }
+
+    #[test]
+    fn launchers_boot_up_by_themselves() {
+        let mut launcher = launcher(LauncherState::Offline);
+        assert_eq!(ticks_to_move(&mut launcher, 1000), Some(600));
+        assert_eq!(launcher.state, LauncherState::Booting);
+        assert_eq!(ticks_to_move(&mut launcher, 1000), Some(50));
+        assert_eq!(launcher.state, LauncherState::Ready);
+        assert_eq!(ticks_to_move(&mut launcher, 10_000), None);
+    }
+
+    #[test]
+    fn launchers_reload_after_a_launch() {
+        let mut launcher = launcher(LauncherState::Launching);
+        assert_eq!(ticks_to_move(&mut launcher, 1000), Some(30));
+        assert_eq!(launcher.state, LauncherState::Reloading);
+        assert_eq!(ticks_to_move(&mut launcher, 1000), Some(150));
+        assert_eq!(launcher.state, LauncherState::Ready);
+    }
+
+    #[test]
+    fn armed_launchers_stay_armed() {
+        let mut launcher = launcher(LauncherState::Armed);
+        assert_eq!(ticks_to_move(&mut launcher, 10_000), None);
+    }
+
+    #[test]
+    fn faults_go_offline_and_recover() {
+        let mut launcher = launcher(LauncherState::Ready);
+        launcher.on_log("Power outage detected at LAX", LogLevel::Warning);
+        assert_eq!(launcher.state, LauncherState::Fault);
+        assert_eq!(ticks_to_move(&mut launcher, 1000), Some(300));
+        assert_eq!(launcher.state, LauncherState::Offline);
+
+        launcher.on_log("LAX rebooted successfully", LogLevel::Info);
+        assert_eq!(launcher.state, LauncherState::Booting);
+    }
+
+    #[test]
+    fn critical_messages_fault_the_launcher() {
+        let mut launcher = launcher(LauncherState::Armed);
+        launcher.on_log("Unknown contact near LAX", LogLevel::Critical);
+        assert_eq!(launcher.state, LauncherState::Fault);
+    }
+
+    #[test]
+    fn an_abort_disarms_the_launcher() {
+        let mut launcher = launcher(LauncherState::Armed);
+        launcher.on_log("Launch from LAX aborted at T-3s", LogLevel::Warning);
+        assert_eq!(launcher.state, LauncherState::Ready);
+    }
+
+    #[test]
+    fn illegal_transitions_are_ignored() {
+        for (state, message, level) in [
+            // Recoveries only bring back launchers that are down.
+            (
+                LauncherState::Ready,
+                "LAX rebooted successfully",
+                LogLevel::Info,
+            ),
+            (
+                LauncherState::Reloading,
+                "LAX ready and stable",
+                LogLevel::Info,
+            ),
+            // Only an armed launcher has anything to abort.
+            (
+                LauncherState::Ready,
+                "Launch from LAX aborted",
+                LogLevel::Warning,
+            ),
+            (
+                LauncherState::Booting,
+                "Launch from LAX aborted",
+                LogLevel::Warning,
+            ),
+            // Nothing stops a missile on its way out.
+            (
+                LauncherState::Launching,
+                "LAX power outage",
+                LogLevel::Critical,
+            ),
+            // Messages about other launchers.
+            (
+                LauncherState::Ready,
+                "LAXX power outage",
+                LogLevel::Critical,
+            ),
+            (
+                LauncherState::Ready,
+                "Power outage at JFK",
+                LogLevel::Critical,
+            ),
+        ] {
+            let mut launcher = launcher(state);
+            launcher.on_log(message, level);
+            assert_eq!(launcher.state, state, "{}", message);
+        }
+    }
+
+    #[test]
+    fn the_same_state_keeps_its_ticks() {
+        let mut launcher = launcher(LauncherState::Offline);
+        launcher.on_tick();
+        launcher.set_state(LauncherState::Offline);
+        assert_eq!(launcher.ticks, 1);
+        launcher.set_state(LauncherState::Booting);
+        assert_eq!(launcher.ticks, 0);
+    }
 
-        if (self.progress * 1000.0) as i64 % 10 == 0 {
-            self.power = (self.power + rand::random::<f64>() * 50.0 - 25.0)
-                .max(0.0)
-                .min(100.0);
+    #[test]
+    fn only_ready_and_armed_launchers_can_launch() {
+        for state in [
+            LauncherState::Offline,
+            LauncherState::Booting,
+            LauncherState::Launching,
+            LauncherState::Reloading,
+            LauncherState::Fault,
+        ] {
+            assert!(!state.can_launch(), "{}", state.as_str());
         }
+        assert!(LauncherState::Ready.can_launch());
+        assert!(LauncherState::Armed.can_launch());
     }
 }
@@ -0,0 +1,314 @@
+//! Tamper-evident audit trail of operator actions.
+//!
+//! Every action is appended to the trail as one JSON line. Each line carries
+//! the hash of the line before it and its own SHA-256 hash over that and its
+//! fields, so editing, inserting or removing any but the last line breaks the
+//! chain, which `verify` reports.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// `prev` of the first line.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What came of an operator action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Done,
+    /// Not available where the operator was, e.g. setting a source on the
+    /// System Monitor tab.
+    Ignored,
+    Accepted,
+    Rejected,
+    LockedOut,
+    Launched,
+    /// The launch was refused by the launcher.
+    Refused,
+    /// Something went wrong carrying it out, such as writing a file.
+    Failed,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Done => "done",
+            Outcome::Ignored => "ignored",
+            Outcome::Accepted => "accepted",
+            Outcome::Rejected => "rejected",
+            Outcome::LockedOut => "locked_out",
+            Outcome::Launched => "launched",
+            Outcome::Refused => "refused",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+/// The fields of a line that its hash covers.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+struct Record {
+    seq: u64,
+    /// Wall clock time, in seconds since the Unix epoch.
+    timestamp: u64,
+    /// Simulated milliseconds since the session started, whole so that the
+    /// line reads back exactly as written.
+    time_ms: u64,
+    action: String,
+    outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    prev: String,
+}
+
+/// A line of the trail, a record sealed with its hash.
+#[derive(Debug, Deserialize, Serialize)]
+struct Line {
+    #[serde(flatten)]
+    record: Record,
+    hash: String,
+}
+
+impl Record {
+    fn hash(&self) -> String {
+        let fields = serde_json::to_string(self).expect("records serialize to JSON");
+        hex::encode(Sha256::digest(fields.as_bytes()))
+    }
+}
+
+/// An audit trail opened for appending, continuing the chain of the lines
+/// already in the file.
+pub struct AuditLog {
+    out: File,
+    seq: u64,
+    prev: String,
+}
+
+impl AuditLog {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<AuditLog> {
+        let path = path.as_ref();
+        let (mut seq, mut prev) = (0, GENESIS.to_string());
+        if path.exists() {
+            let mut last = None;
+            for line in BufReader::new(File::open(path)?).lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    last = Some(line);
+                }
+            }
+            if let Some(last) = last {
+                let line: Line = serde_json::from_str(&last).map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("last line of the audit trail: {}", err),
+                    )
+                })?;
+                seq = line.record.seq + 1;
+                prev = line.hash;
+            }
+        }
+        let out = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(AuditLog { out, seq, prev })
+    }
+
+    /// Append an action at `time` simulated time into the session. Every
+    /// line is written through at once, so a crash loses nothing.
+    pub fn append(
+        &mut self,
+        time: Duration,
+        action: &str,
+        outcome: Outcome,
+        detail: Option<String>,
+    ) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let record = Record {
+            seq: self.seq,
+            timestamp,
+            time_ms: time.as_millis() as u64,
+            action: action.to_string(),
+            outcome,
+            detail,
+            prev: self.prev.clone(),
+        };
+        let line = Line {
+            hash: record.hash(),
+            record,
+        };
+        writeln!(self.out, "{}", serde_json::to_string(&line)?)?;
+        self.seq += 1;
+        self.prev = line.hash;
+        Ok(())
+    }
+}
+
+/// Result of checking an audit trail with `verify`.
+#[derive(Debug, Default)]
+pub struct Verification {
+    pub records: usize,
+    /// One message per broken line, with its line number.
+    pub problems: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_intact(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check every line's hash and its link to the line before.
+pub fn verify<P: AsRef<Path>>(path: P) -> io::Result<Verification> {
+    let mut verification = Verification::default();
+    let mut expected = Some((0, GENESIS.to_string()));
+    for (i, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let text = line?;
+        if text.trim().is_empty() {
+            continue;
+        }
+        let mut problem = |msg: String| {
+            verification
+                .problems
+                .push(format!("line {}: {}", i + 1, msg))
+        };
+        let line = match serde_json::from_str::<Line>(&text) {
+            Ok(line) => line,
+            Err(err) => {
+                problem(format!("not an audit record: {}", err));
+                expected = None;
+                continue;
+            }
+        };
+        // Catch fields added to or reformatted in a line, which the hash
+        // alone would miss.
+        if serde_json::to_string(&line).ok().as_deref() != Some(text.as_str()) {
+            problem("is not as it was written".to_string());
+        }
+        if let Some((seq, prev)) = &expected {
+            if line.record.seq != *seq {
+                problem(format!("expected record {}, got {}", seq, line.record.seq));
+            }
+            if line.record.prev != *prev {
+                problem("does not follow the line before".to_string());
+            }
+        }
+        if line.record.hash() != line.hash {
+            problem("hash does not match its content".to_string());
+        }
+        verification.records += 1;
+        expected = Some((line.record.seq + 1, line.hash));
+    }
+    Ok(verification)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, path::PathBuf, process};
+
+    /// A trail of three actions in a file of its own.
+    fn trail(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("audit-{}-{}.jsonl", process::id(), name));
+        let _ = fs::remove_file(&path);
+        let mut audit = AuditLog::open(&path).unwrap();
+        for (i, action) in ["next_tab", "set_source", "launch"].iter().enumerate() {
+            let time = Duration::from_millis(100) * (i as u32 * 7 + 1);
+            audit.append(time, action, Outcome::Done, None).unwrap();
+        }
+        path
+    }
+
+    /// Rewrite the lines of the trail at `path` with `edit`.
+    fn tamper(path: &Path, edit: impl FnOnce(&mut Vec<String>)) {
+        let text = fs::read_to_string(path).unwrap();
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        edit(&mut lines);
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    fn problems(path: &Path) -> Vec<String> {
+        let problems = verify(path).unwrap().problems;
+        fs::remove_file(path).unwrap();
+        problems
+    }
+
+    #[test]
+    fn untouched_trail_is_intact() {
+        let path = trail("untouched");
+        let mut audit = AuditLog::open(&path).unwrap();
+        audit
+            .append(Duration::from_millis(3300), "quit", Outcome::Done, None)
+            .unwrap();
+        let verification = verify(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(verification.is_intact(), "{:?}", verification.problems);
+        assert_eq!(verification.records, 4);
+    }
+
+    #[test]
+    fn edited_line_is_caught() {
+        let path = trail("edited");
+        tamper(&path, |lines| {
+            lines[1] = lines[1].replace("\"done\"", "\"rejected\"");
+        });
+        assert_eq!(problems(&path), ["line 2: hash does not match its content"]);
+    }
+
+    #[test]
+    fn removed_line_is_caught() {
+        let path = trail("removed");
+        tamper(&path, |lines| {
+            lines.remove(1);
+        });
+        assert_eq!(
+            problems(&path),
+            [
+                "line 2: expected record 1, got 2",
+                "line 2: does not follow the line before"
+            ]
+        );
+    }
+
+    #[test]
+    fn swapped_lines_are_caught() {
+        let path = trail("swapped");
+        tamper(&path, |lines| lines.swap(0, 1));
+        let problems = problems(&path);
+        assert!(
+            problems.iter().any(|p| p.starts_with("line 1:")),
+            "{:?}",
+            problems
+        );
+        assert!(
+            problems.iter().any(|p| p.starts_with("line 2:")),
+            "{:?}",
+            problems
+        );
+    }
+
+    #[test]
+    fn reformatted_line_is_caught() {
+        let path = trail("reformatted");
+        tamper(&path, |lines| {
+            lines[2] = lines[2].replacen(",", ", ", 1);
+        });
+        assert_eq!(problems(&path), ["line 3: is not as it was written"]);
+    }
+
+    #[test]
+    fn garbage_line_is_caught() {
+        let path = trail("garbage");
+        tamper(&path, |lines| lines.insert(1, "launch".to_string()));
+        let problems = problems(&path);
+        assert!(
+            problems[0].starts_with("line 2: not an audit record"),
+            "{:?}",
+            problems
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::{error::Error, fmt, time::Duration};
+use subtle::ConstantTimeEq;
+
+const SCHEME: &str = "sha256";
+const SALT_LEN: usize = 16;
+
+/// A salted SHA-256 hash of a launch code, written as `sha256$<salt>$<digest>`
+/// with both parts hex encoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeHash {
+    salt: Vec<u8>,
+    digest: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct CodeHashError(String);
+
+impl fmt::Display for CodeHashError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid code hash: {}", self.0)
+    }
+}
+
+impl Error for CodeHashError {}
+
+impl CodeHash {
+    /// Hash `code` with a freshly generated random salt.
+    pub fn new(code: &str) -> CodeHash {
+        let salt: [u8; SALT_LEN] = rand::thread_rng().gen();
+        CodeHash::with_salt(code, &salt)
+    }
+
+    pub fn with_salt(code: &str, salt: &[u8]) -> CodeHash {
+        CodeHash {
+            salt: salt.to_vec(),
+            digest: digest(code, salt),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<CodeHash, CodeHashError> {
+        let mut parts = s.trim().split('$');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(SCHEME), Some(salt), Some(digest), None) => {
+                let salt = hex::decode(salt)
+                    .map_err(|err| CodeHashError(format!("salt is not hex: {}", err)))?;
+                let digest = hex::decode(digest)
+                    .map_err(|err| CodeHashError(format!("digest is not hex: {}", err)))?;
+                if digest.len() != Sha256::output_size() {
+                    return Err(CodeHashError(format!(
+                        "digest must be {} bytes, got {}",
+                        Sha256::output_size(),
+                        digest.len()
+                    )));
+                }
+                Ok(CodeHash { salt, digest })
+            }
+            _ => Err(CodeHashError(format!(
+                "expected {}$<salt>$<digest>",
+                SCHEME
+            ))),
+        }
+    }
+
+    /// Compare `code` against the hash in constant time.
+    pub fn verify(&self, code: &str) -> bool {
+        digest(code, &self.salt).ct_eq(&self.digest).into()
+    }
+}
+
+impl fmt::Display for CodeHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}${}${}",
+            SCHEME,
+            hex::encode(&self.salt),
+            hex::encode(&self.digest)
+        )
+    }
+}
+
+fn digest(code: &str, salt: &[u8]) -> Vec<u8> {
+    Sha256::new()
+        .chain_update(salt)
+        .chain_update(code.as_bytes())
+        .finalize()
+        .to_vec()
+}
+
+/// Result of submitting a code to `Authorization::submit`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Attempt {
+    Accepted,
+    Rejected,
+    /// The code is one an earlier operator already entered. Every operator
+    /// needs a code of their own, but this is no guess at one, so it does not
+    /// count towards the lockout.
+    Reused,
+    /// The code was wrong and the panel is now locked.
+    LockedOut(Duration),
+    /// Every code was accepted already, so there was nothing to check.
+    NotPending,
+}
+
+/// Countdowns reported by `Authorization::on_tick`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expired {
+    /// The confirmation window ran out before every operator was accepted.
+    Window,
+    /// The lockout after too many failed attempts is over.
+    Lockout,
+}
+
+/// One launch code per operator. The codes have to be entered in order, and
+/// once the first one is accepted the rest must follow within `window`.
+///
+/// After `max_failures` wrong codes in a row the panel is locked for
+/// `cooldown`, doubling with every further lockout. Both count down in real
+/// time, as passed to `on_tick`, whatever the speed of the simulation.
+pub struct Authorization {
+    pub codes: Vec<CodeHash>,
+    pub accepted: usize,
+    pub window: Duration,
+    /// Time left to enter the remaining codes.
+    pub remaining: Option<Duration>,
+    pub failures: u32,
+    pub max_failures: u32,
+    pub lockouts: u32,
+    pub cooldown: Duration,
+    /// Time left until the panel unlocks.
+    pub locked: Option<Duration>,
+}
+
+impl Authorization {
+    pub fn new(codes: Vec<CodeHash>, window: Duration) -> Authorization {
+        Authorization {
+            codes,
+            accepted: 0,
+            window,
+            remaining: None,
+            failures: 0,
+            max_failures: 3,
+            lockouts: 0,
+            cooldown: Duration::from_secs(30),
+            locked: None,
+        }
+    }
+
+    pub fn with_lockout(mut self, max_failures: u32, cooldown: Duration) -> Authorization {
+        self.max_failures = max_failures.max(1);
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.accepted == self.codes.len()
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.is_some()
+    }
+
+    /// Whether the lockout or the confirmation window is counting down.
+    pub fn is_counting(&self) -> bool {
+        self.locked.is_some() || self.remaining.is_some()
+    }
+
+    /// Check `code` against the next pending operator code.
+    pub fn submit(&mut self, code: &str) -> Attempt {
+        if let Some(locked) = self.locked {
+            return Attempt::LockedOut(locked);
+        }
+        let Some(hash) = self.codes.get(self.accepted) else {
+            return Attempt::NotPending;
+        };
+        if self.codes[..self.accepted]
+            .iter()
+            .any(|accepted| accepted.verify(code))
+        {
+            return Attempt::Reused;
+        }
+        if !hash.verify(code) {
+            self.failures += 1;
+            if self.failures < self.max_failures {
+                return Attempt::Rejected;
+            }
+            let locked = self.cooldown.saturating_mul(1 << self.lockouts.min(16));
+            self.failures = 0;
+            self.lockouts += 1;
+            self.locked = Some(locked);
+            self.reset();
+            return Attempt::LockedOut(locked);
+        }
+        self.failures = 0;
+        self.accepted += 1;
+        if self.accepted == 1 && self.codes.len() > 1 {
+            self.remaining = Some(self.window);
+        }
+        Attempt::Accepted
+    }
+
+    pub fn reset(&mut self) {
+        self.accepted = 0;
+        self.remaining = None;
+    }
+
+    /// Count down the confirmation window and the lockout by `elapsed`.
+    pub fn on_tick(&mut self, elapsed: Duration) -> Option<Expired> {
+        if let Some(locked) = self.locked {
+            if locked <= elapsed {
+                self.locked = None;
+                return Some(Expired::Lockout);
+            }
+            self.locked = Some(locked - elapsed);
+        }
+        match self.remaining {
+            Some(remaining) if remaining <= elapsed => {
+                self.reset();
+                Some(Expired::Window)
+            }
+            Some(remaining) => {
+                self.remaining = Some(remaining - elapsed);
+                None
+            }
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authorization(codes: &[&str]) -> Authorization {
+        let codes = codes.iter().map(|code| CodeHash::new(code)).collect();
+        Authorization::new(codes, Duration::from_secs(30))
+    }
+
+    /// `1234` salted with the bytes 0x00 to 0xff in steps of 0x11.
+    const HASH: &str = "sha256$00112233445566778899aabbccddeeff$\
+        8c8fb15e510c23192b9ecceb9cc163a6062c5c05faa10a68d900fe73cb0af2f3";
+
+    #[test]
+    fn parsed_hash_verifies_its_code_only() {
+        let hash = CodeHash::parse(HASH).unwrap();
+        assert!(hash.verify("1234"));
+        assert!(!hash.verify("1235"));
+        assert!(!hash.verify(""));
+        assert_eq!(hash.to_string(), HASH);
+    }
+
+    #[test]
+    fn hash_parses_back_from_its_display() {
+        let hash = CodeHash::new("0000");
+        let parsed = CodeHash::parse(&format!("  {}\n", hash)).unwrap();
+        assert_eq!(parsed, hash);
+        assert!(parsed.verify("0000"));
+    }
+
+    #[test]
+    fn salts_differ() {
+        assert_ne!(CodeHash::new("1234"), CodeHash::new("1234"));
+    }
+
+    #[test]
+    fn malformed_hashes_are_rejected() {
+        let digest = "8c8fb15e510c23192b9ecceb9cc163a6062c5c05faa10a68d900fe73cb0af2f3";
+        for (hash, problem) in [
+            ("", "expected sha256$<salt>$<digest>"),
+            (
+                &format!("md5$00${}", digest),
+                "expected sha256$<salt>$<digest>",
+            ),
+            ("sha256$00", "expected sha256$<salt>$<digest>"),
+            (
+                &format!("sha256$00${}$00", digest),
+                "expected sha256$<salt>$<digest>",
+            ),
+            (&format!("sha256$0g${}", digest), "salt is not hex"),
+            ("sha256$00$xyz", "digest is not hex"),
+            ("sha256$00$00ff", "digest must be 32 bytes, got 2"),
+        ] {
+            let err = CodeHash::parse(hash).unwrap_err().to_string();
+            assert!(err.contains(problem), "{:?}: {}", hash, err);
+        }
+    }
+
+    #[test]
+    fn nothing_pending_is_not_a_failure() {
+        let mut authorization = authorization(&["1234"]);
+        assert_eq!(authorization.submit("1234"), Attempt::Accepted);
+        assert_eq!(authorization.submit("1234"), Attempt::NotPending);
+        assert_eq!(authorization.failures, 0);
+    }
+
+    #[test]
+    fn codes_are_checked_in_order() {
+        let mut authorization = authorization(&["1234", "5678"]);
+        assert_eq!(authorization.submit("5678"), Attempt::Rejected);
+        assert_eq!(authorization.submit("1234"), Attempt::Accepted);
+        assert_eq!(authorization.submit("5678"), Attempt::Accepted);
+        assert!(authorization.is_complete());
+    }
+
+    #[test]
+    fn an_accepted_code_cannot_be_entered_again() {
+        // Hash files can list the same code twice under different salts.
+        for codes in [["1234", "5678"], ["1234", "1234"]] {
+            let mut authorization = authorization(&codes);
+            assert_eq!(authorization.submit("1234"), Attempt::Accepted);
+            assert_eq!(authorization.submit("1234"), Attempt::Reused);
+            assert_eq!(authorization.failures, 0);
+            assert!(!authorization.is_complete());
+        }
+    }
+
+    #[test]
+    fn repeated_failures_lock_the_panel() {
+        let mut authorization = authorization(&["1234"]).with_lockout(2, Duration::from_secs(10));
+        assert_eq!(authorization.submit("0000"), Attempt::Rejected);
+        assert_eq!(authorization.failures, 1);
+        let locked = Duration::from_secs(10);
+        assert_eq!(authorization.submit("0000"), Attempt::LockedOut(locked));
+        assert!(authorization.is_locked());
+        assert_eq!(authorization.failures, 0);
+        // Not even the right code gets through until the lockout is over.
+        assert_eq!(authorization.submit("1234"), Attempt::LockedOut(locked));
+        assert_eq!(authorization.accepted, 0);
+    }
+
+    #[test]
+    fn a_success_resets_the_failures() {
+        let mut authorization = authorization(&["1234", "5678"]).with_lockout(2, Duration::ZERO);
+        assert_eq!(authorization.submit("0000"), Attempt::Rejected);
+        assert_eq!(authorization.submit("1234"), Attempt::Accepted);
+        assert_eq!(authorization.failures, 0);
+        assert_eq!(authorization.submit("0000"), Attempt::Rejected);
+        assert!(!authorization.is_locked());
+    }
+
+    #[test]
+    fn the_cooldown_doubles_with_every_lockout() {
+        let cooldown = Duration::from_secs(10);
+        let mut authorization = authorization(&["1234"]).with_lockout(1, cooldown);
+        for lockout in 0..4 {
+            let locked = cooldown * (1 << lockout);
+            assert_eq!(authorization.submit("0000"), Attempt::LockedOut(locked));
+            assert_eq!(authorization.on_tick(locked), Some(Expired::Lockout));
+        }
+        assert_eq!(authorization.lockouts, 4);
+    }
+
+    #[test]
+    fn the_lockout_expires_after_the_cooldown() {
+        let second = Duration::from_secs(1);
+        let mut authorization = authorization(&["1234"]).with_lockout(1, second * 3);
+        authorization.submit("0000");
+        assert_eq!(authorization.on_tick(second), None);
+        assert_eq!(authorization.on_tick(second), None);
+        assert_eq!(authorization.locked, Some(second));
+        assert_eq!(authorization.on_tick(second), Some(Expired::Lockout));
+        assert!(!authorization.is_locked());
+        assert_eq!(authorization.on_tick(second), None);
+        assert_eq!(authorization.submit("1234"), Attempt::Accepted);
+    }
+
+    #[test]
+    fn a_lockout_resets_the_accepted_codes() {
+        let mut authorization = authorization(&["1234", "5678"]).with_lockout(1, Duration::ZERO);
+        assert_eq!(authorization.submit("1234"), Attempt::Accepted);
+        assert!(matches!(
+            authorization.submit("0000"),
+            Attempt::LockedOut(_)
+        ));
+        assert_eq!(authorization.accepted, 0);
+        assert_eq!(authorization.remaining, None);
+    }
+}
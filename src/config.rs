@@ -0,0 +1,175 @@
+//! User preferences loaded from a TOML file with `--config`.
+
+use crate::keys::{Action, Key, Keymap};
+use serde::Deserialize;
+use std::{collections::HashMap, error::Error, fmt, fs, io, path::Path};
+
+/// A single key or a list of keys, so `quit = "ctrl+q"` and
+/// `delete = ["Backspace", "ctrl+h"]` both work.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Binding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Binding {
+    fn names(&self) -> &[String] {
+        match self {
+            Binding::One(name) => std::slice::from_ref(name),
+            Binding::Many(names) => names,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Keys per action, replacing the default keys of the listed actions.
+    #[serde(default)]
+    pub keys: HashMap<Action, Binding>,
+    /// A preset name or the path of a theme file.
+    pub theme: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(String),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "cannot read config: {}", err),
+            ConfigError::Parse(msg) => write!(f, "cannot parse config: {}", msg),
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid config:")?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl Config {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        let content = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        let config: Config =
+            toml::from_str(&content).map_err(|err| ConfigError::Parse(err.to_string()))?;
+        config.keymap()?;
+        Ok(config)
+    }
+
+    /// The default keymap with the configured bindings applied, reporting
+    /// every key that cannot be parsed, every action left without keys and
+    /// every key bound to more than one action.
+    pub fn keymap(&self) -> Result<Keymap, ConfigError> {
+        let mut keymap = Keymap::default();
+        let mut problems = Vec::new();
+        for (action, binding) in &self.keys {
+            if binding.names().is_empty() {
+                problems.push(format!("keys.{}: no keys given", action.as_str()));
+            }
+            let mut keys = Vec::new();
+            for name in binding.names() {
+                match Key::parse(name) {
+                    Some(key) => keys.push(key),
+                    None => {
+                        problems.push(format!("keys.{}: unknown key {:?}", action.as_str(), name))
+                    }
+                }
+            }
+            keymap.bind(*action, keys);
+        }
+        for (key, actions) in keymap.conflicts() {
+            let actions: Vec<_> = actions.iter().map(|action| action.as_str()).collect();
+            problems.push(format!(
+                "keys: {} is bound to {}",
+                key,
+                actions.join(" and ")
+            ));
+        }
+        if problems.is_empty() {
+            Ok(keymap)
+        } else {
+            problems.sort();
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+
+    fn problems(toml: &str) -> Vec<String> {
+        let config: Config = toml::from_str(toml).unwrap();
+        match config.keymap() {
+            Err(ConfigError::Invalid(problems)) => problems,
+            other => panic!("expected problems, got {:?}", other.map(drop)),
+        }
+    }
+
+    #[test]
+    fn bindings_replace_the_default_keys() {
+        let config: Config = toml::from_str(
+            r#"
+            [keys]
+            quit = "ctrl+q"
+            delete = ["Backspace", "ctrl+h"]
+            "#,
+        )
+        .unwrap();
+        let keymap = config.keymap().unwrap();
+        assert_eq!(
+            keymap.actions(Key::parse("ctrl+q").unwrap()),
+            [Action::Quit]
+        );
+        assert!(keymap.actions(KeyCode::Char('q').into()).is_empty());
+        assert_eq!(
+            keymap.actions(Key::parse("ctrl+h").unwrap()),
+            [Action::Delete]
+        );
+        assert!(keymap.actions(KeyCode::Delete.into()).is_empty());
+        assert_eq!(keymap.actions(KeyCode::Enter.into()), [Action::Submit]);
+    }
+
+    #[test]
+    fn a_key_bound_twice_is_a_conflict() {
+        assert_eq!(
+            problems("[keys]\nquit = \"s\""),
+            ["keys: s is bound to quit and set_source"]
+        );
+        assert_eq!(
+            problems("[keys]\nabort = [\"x\"]\ncycle_speed = [\"x\", \"y\"]"),
+            ["keys: x is bound to abort and cycle_speed"]
+        );
+    }
+
+    #[test]
+    fn an_action_needs_a_key() {
+        assert_eq!(
+            problems("[keys]\nabort = []"),
+            ["keys.abort: no keys given"]
+        );
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        assert_eq!(
+            problems("[keys]\nquit = \"hyper+q\"\nabort = []\nnext_tab = \"Left\""),
+            [
+                "keys.abort: no keys given",
+                "keys.quit: unknown key \"hyper+q\"",
+                "keys: Left is bound to next_tab and prev_tab",
+            ]
+        );
+    }
+}
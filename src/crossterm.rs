@@ -1,20 +1,40 @@
-use crate::{app::App, ui};
+use crate::{
+    app::{App, TICK_RATE},
+    keys::Key,
+    record::{Input, InputLog, Recorder},
+    ui,
+};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use std::{
     error::Error,
     io,
+    path::PathBuf,
     time::{Duration, Instant},
 };
 use tui::{
     backend::{Backend, CrosstermBackend},
+    layout::Rect,
     Terminal,
 };
 
-pub fn run(correct_code: String) -> Result<(), Box<dyn Error>> {
+/// Optional recording and replay of a session.
+#[derive(Default)]
+pub struct Session {
+    /// Write every frame and input to this asciicast file.
+    pub record: Option<PathBuf>,
+    /// Feed these inputs to the app instead of the keyboard until they run out.
+    pub replay: Option<InputLog>,
+}
+
+/// Run the dashboard, ticking the simulation every `app.tick_rate` at 1x
+/// speed and redrawing it every `frame_rate`.
+pub fn run(app: App, frame_rate: Duration, session: Session) -> Result<(), Box<dyn Error>> {
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -22,9 +42,7 @@ pub fn run(correct_code: String) -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // create app and run it
-    let app = App::new("Missile Launcher", correct_code);
-    let res = run_app(&mut terminal, app, Duration::from_millis(100));
+    let res = run_app(&mut terminal, app, frame_rate, session);
 
     // restore terminal
     disable_raw_mode()?;
@@ -42,40 +60,154 @@ pub fn run(correct_code: String) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// How far the simulation is into its current tick. It runs `speed` times as
+/// fast as real time at a tick per `app.tick_rate`, and stands still while
+/// paused.
+struct Clock {
+    last: Instant,
+    /// Simulated time since the last tick.
+    progress: Duration,
+}
+
+impl Clock {
+    fn new() -> Clock {
+        Clock {
+            last: Instant::now(),
+            progress: Duration::ZERO,
+        }
+    }
+
+    /// Simulated time per real time.
+    fn scale(&self, app: &App) -> f64 {
+        f64::from(app.speed) * TICK_RATE.as_secs_f64() / app.tick_rate.as_secs_f64()
+    }
+
+    fn advance(&mut self, app: &App) {
+        let now = Instant::now();
+        if !app.paused {
+            self.progress += (now - self.last).mul_f64(self.scale(app));
+        }
+        self.last = now;
+    }
+
+    /// Real time until the next tick is due, `Duration::MAX` while paused.
+    fn until_tick(&self, app: &App) -> Duration {
+        if app.paused {
+            return Duration::MAX;
+        }
+        TICK_RATE
+            .saturating_sub(self.progress)
+            .div_f64(self.scale(app))
+    }
+}
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
-    tick_rate: Duration,
+    frame_rate: Duration,
+    mut session: Session,
 ) -> io::Result<()> {
-    let mut last_tick = Instant::now();
+    let mut recorder = match &session.record {
+        Some(path) => {
+            let size = terminal.size()?;
+            Some(Recorder::create(path, size.width, size.height, app.seed)?)
+        }
+        None => None,
+    };
+    let mut clock = Clock::new();
+    let mut last_frame: Option<Instant> = None;
     loop {
-        terminal.draw(|f| ui::draw(f, &mut app))?;
+        clock.advance(&app);
+        if last_frame.is_none_or(|frame| frame.elapsed() >= frame_rate) {
+            let frame = terminal.draw(|f| ui::draw(f, &mut app))?;
+            if let Some(recorder) = &mut recorder {
+                recorder.frame(frame.buffer)?;
+            }
+            last_frame = Some(Instant::now());
+        }
 
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
+        let replaying = session
+            .replay
+            .as_ref()
+            .is_some_and(|log| !log.inputs.is_empty());
+        if let Some(log) = &mut session.replay {
+            replay_due(&mut app, log, &mut recorder)?;
+        }
+
+        let next_frame = last_frame.map_or(Duration::ZERO, |frame| {
+            frame_rate.saturating_sub(frame.elapsed())
+        });
+        let timeout = next_frame.min(clock.until_tick(&app));
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Enter
-                    | KeyCode::Backspace
-                    | KeyCode::Delete
-                    | KeyCode::Esc
-                    | KeyCode::Char(_) => app.on_key(key.code),
-                    KeyCode::Left => app.on_left(),
-                    KeyCode::Up => app.on_up(),
-                    KeyCode::Right => app.on_right(),
-                    KeyCode::Down => app.on_down(),
-                    _ => {}
+            clock.advance(&app);
+            let input = match event::read()? {
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                    ..
+                }) if replaying => return Ok(()),
+                Event::Resize(width, height) => {
+                    terminal.resize(Rect::new(0, 0, width, height))?;
+                    if let Some(recorder) = &mut recorder {
+                        recorder.resize(width, height)?;
+                    }
+                    None
                 }
+                _ if replaying => None,
+                Event::Key(key) => Some(Input::Key(Key::from(key))),
+                Event::Mouse(mouse) => Some(Input::Mouse(mouse)),
+                _ => None,
+            };
+            if let Some(input) = input {
+                apply(&mut app, input, &mut recorder)?;
             }
         }
-        if last_tick.elapsed() >= tick_rate {
+        clock.advance(&app);
+        // Catch up on every tick that is due, so slow frames do not slow
+        // the simulation down. A replay feeds each input in before the tick
+        // after the one it was recorded on, however many ticks a frame runs.
+        while !app.paused && !app.should_quit && clock.progress >= TICK_RATE {
             app.on_tick();
-            last_tick = Instant::now();
+            clock.progress -= TICK_RATE;
+            if let Some(log) = &mut session.replay {
+                replay_due(&mut app, log, &mut recorder)?;
+            }
         }
         if app.should_quit {
+            if let Some(recorder) = &mut recorder {
+                recorder.flush()?;
+            }
+            if app.log_export.is_some() {
+                app.export_logs()?;
+            }
             return Ok(());
         }
     }
 }
+
+/// Feed the app every replayed input recorded on or before its current tick.
+fn replay_due(
+    app: &mut App,
+    log: &mut InputLog,
+    recorder: &mut Option<Recorder>,
+) -> io::Result<()> {
+    while let Some(&(tick, input)) = log.inputs.front() {
+        if tick > app.ticks {
+            break;
+        }
+        log.inputs.pop_front();
+        apply(app, input, recorder)?;
+    }
+    Ok(())
+}
+
+fn apply(app: &mut App, input: Input, recorder: &mut Option<Recorder>) -> io::Result<()> {
+    if let Some(recorder) = recorder {
+        recorder.input(app.ticks, input)?;
+    }
+    match input {
+        Input::Key(key) => app.handle_key(key),
+        Input::Mouse(mouse) => app.on_mouse(mouse),
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Great-circle helpers. Coordinates are `(latitude, longitude)` in degrees,
+//! the same order used by `Launcher::coords`.
+
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+fn to_vector((lat, lon): (f64, f64)) -> [f64; 3] {
+    let (lat, lon) = (lat.to_radians(), lon.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+fn from_vector([x, y, z]: [f64; 3]) -> (f64, f64) {
+    (
+        z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        y.atan2(x).to_degrees(),
+    )
+}
+
+/// Central angle between two points in radians (haversine formula).
+pub fn central_angle(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (to.1 - from.1).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * a.sqrt().min(1.0).asin()
+}
+
+/// Great-circle distance between two points in kilometres.
+pub fn distance(from: (f64, f64), to: (f64, f64)) -> f64 {
+    central_angle(from, to) * EARTH_RADIUS_KM
+}
+
+/// The point at `fraction` (0.0 to 1.0) of the way along the great-circle arc.
+/// Every great circle through a point passes its antipode, so the arc
+/// between antipodes heads north, or from a pole along the prime meridian.
+pub fn interpolate(from: (f64, f64), to: (f64, f64), fraction: f64) -> (f64, f64) {
+    let angle = central_angle(from, to);
+    if angle.abs() < f64::EPSILON {
+        return from;
+    }
+    let p = to_vector(from);
+    let heading = [to_vector(to), [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]]
+        .into_iter()
+        .find_map(|towards| tangent(p, towards))
+        .unwrap();
+    let (sin, cos) = (fraction * angle).sin_cos();
+    from_vector([
+        cos * p[0] + sin * heading[0],
+        cos * p[1] + sin * heading[1],
+        cos * p[2] + sin * heading[2],
+    ])
+}
+
+/// The unit vector at `p` along the surface towards `q`, `None` when `q` is
+/// `p` or its antipode and no direction leads there in particular.
+fn tangent(p: [f64; 3], q: [f64; 3]) -> Option<[f64; 3]> {
+    let dot = p[0] * q[0] + p[1] * q[1] + p[2] * q[2];
+    let t = [q[0] - dot * p[0], q[1] - dot * p[1], q[2] - dot * p[2]];
+    let norm = (t[0] * t[0] + t[1] * t[1] + t[2] * t[2]).sqrt();
+    (norm > 1e-9).then(|| [t[0] / norm, t[1] / norm, t[2] / norm])
+}
+
+/// `steps + 1` evenly spaced points along the arc, endpoints included.
+pub fn arc(from: (f64, f64), to: (f64, f64), steps: usize) -> Vec<(f64, f64)> {
+    (0..=steps)
+        .map(|i| interpolate(from, to, i as f64 / steps as f64))
+        .collect()
+}
+
+/// Parse `"lat, lon"` (comma or whitespace separated) into valid coordinates.
+pub fn parse_coords(input: &str) -> Option<(f64, f64)> {
+    let mut parts = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty());
+    let lat: f64 = parts.next()?.parse().ok()?;
+    let lon: f64 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon)
+    {
+        return None;
+    }
+    Some((lat, lon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn assert_near(actual: (f64, f64), expected: (f64, f64)) {
+        let angle = central_angle(actual, expected);
+        assert!(angle < 1e-6, "{:?} is not {:?}", actual, expected);
+    }
+
+    /// Every point of the arc lies `fraction` of the way there.
+    fn assert_arc(from: (f64, f64), to: (f64, f64)) {
+        let angle = central_angle(from, to);
+        for (i, point) in arc(from, to, 10).into_iter().enumerate() {
+            let fraction = i as f64 / 10.0;
+            assert!(point.0.is_finite() && point.1.is_finite(), "{:?}", point);
+            let travelled = central_angle(from, point);
+            assert!((travelled - fraction * angle).abs() < 1e-6, "{:?}", point);
+            let left = central_angle(point, to);
+            assert!(
+                (left - (1.0 - fraction) * angle).abs() < 1e-6,
+                "{:?}",
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn arcs_follow_the_great_circle() {
+        assert_near(interpolate((0.0, 0.0), (0.0, 90.0), 0.5), (0.0, 45.0));
+        assert_near(interpolate((0.0, 0.0), (90.0, 0.0), 0.5), (45.0, 0.0));
+        assert_arc((25.0, 121.5), (34.0, -118.2));
+        assert_arc((51.5, -0.1), (-33.9, 151.2));
+    }
+
+    #[test]
+    fn arcs_between_antipodes_are_well_defined() {
+        for from in [(0.0, 0.0), (25.0, 121.5), (-45.0, -170.0), (90.0, 0.0)] {
+            let to = (-from.0, from.1 - 180.0);
+            assert!((central_angle(from, to) - PI).abs() < 1e-6);
+            assert_arc(from, to);
+            assert_near(interpolate(from, to, 1.0), to);
+        }
+        // Heading north from the equator, over the pole.
+        assert_near(interpolate((0.0, 0.0), (0.0, 180.0), 0.5), (90.0, 0.0));
+    }
+
+    #[test]
+    fn nearly_antipodal_arcs_stay_on_the_sphere() {
+        assert_arc((10.0, 20.0), (-10.0 + 1e-7, -160.0));
+        assert_arc((10.0, 20.0), (-10.0, -160.0 + 1e-12));
+    }
+
+    #[test]
+    fn coords_are_range_checked() {
+        assert_eq!(parse_coords("25.0, 121.5"), Some((25.0, 121.5)));
+        assert_eq!(parse_coords(" -33.9  151.2 "), Some((-33.9, 151.2)));
+        for input in ["", "25.0", "91, 0", "0, 181", "1, 2, 3", "north, east"] {
+            assert_eq!(parse_coords(input), None, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Drive `App` and `ui::draw` without a terminal, for snapshots and scripting.
+
+use crate::{app::App, keys::Key, ui};
+use crossterm::event::MouseEvent;
+use std::io;
+use tui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+    Terminal,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// One step of a scripted session.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    Key(Key),
+    /// Drawn first, so it hits what is on screen at that point.
+    Mouse(MouseEvent),
+    Ticks(u64),
+}
+
+pub struct Headless<'a> {
+    pub app: App<'a>,
+    terminal: Terminal<TestBackend>,
+}
+
+impl<'a> Headless<'a> {
+    pub fn new(app: App<'a>, width: u16, height: u16) -> io::Result<Headless<'a>> {
+        Ok(Headless {
+            app,
+            terminal: Terminal::new(TestBackend::new(width, height))?,
+        })
+    }
+
+    pub fn press<K: Into<Key>>(&mut self, key: K) {
+        self.app.handle_key(key.into());
+    }
+
+    /// Hits the areas of the last drawn frame, so draw before clicking.
+    pub fn mouse(&mut self, event: MouseEvent) {
+        self.app.on_mouse(event);
+    }
+
+    pub fn tick(&mut self, ticks: u64) {
+        for _ in 0..ticks {
+            self.app.on_tick();
+        }
+    }
+
+    pub fn run(&mut self, steps: &[Step]) -> io::Result<()> {
+        for step in steps {
+            match *step {
+                Step::Key(key) => self.press(key),
+                Step::Mouse(event) => {
+                    self.draw()?;
+                    self.mouse(event);
+                }
+                Step::Ticks(ticks) => self.tick(ticks),
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the current state and return the resulting buffer.
+    pub fn draw(&mut self) -> io::Result<&Buffer> {
+        let app = &mut self.app;
+        self.terminal.draw(|f| ui::draw(f, app))?;
+        Ok(self.terminal.backend().buffer())
+    }
+}
+
+/// Run `steps` against `app` and render a single frame.
+pub fn render(app: App, width: u16, height: u16, steps: &[Step]) -> io::Result<Buffer> {
+    let mut headless = Headless::new(app, width, height)?;
+    headless.run(steps)?;
+    Ok(headless.draw()?.clone())
+}
+
+/// The buffer as plain text, one line per row.
+pub fn to_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut skip = 0;
+        for x in 0..buffer.area.width {
+            let cell = buffer.get(buffer.area.x + x, buffer.area.y + y);
+            if skip == 0 {
+                out.push_str(&cell.symbol);
+            }
+            skip = cell.symbol.width().max(1).max(skip) - 1;
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The buffer with its colors and modifiers as ANSI escape sequences.
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut skip = 0;
+        let mut style = None;
+        for x in 0..buffer.area.width {
+            let cell = buffer.get(buffer.area.x + x, buffer.area.y + y);
+            if skip == 0 {
+                let current = (cell.fg, cell.bg, cell.modifier);
+                if style != Some(current) {
+                    out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                    style = Some(current);
+                }
+                out.push_str(&cell.symbol);
+            }
+            skip = cell.symbol.width().max(1).max(skip) - 1;
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// The SGR escape sequence that selects the given colors and modifiers.
+pub fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color(fg, false));
+    codes.extend(color(bg, true));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(i) => return Some(format!("{};5;{}", base + 8, i)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    };
+    Some(code.to_string())
+}
@@ -0,0 +1,335 @@
+//! Key combinations and the actions they are bound to.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::{collections::HashMap, fmt};
+
+/// A key together with its modifiers, written as e.g. `ctrl+q`, `Enter` or `t`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        // The case of a character already tells whether shift was held.
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            _ => code,
+        };
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Key { code, modifiers }
+    }
+
+    /// Parse `ctrl+alt+x` style combinations, names are case-insensitive.
+    pub fn parse(s: &str) -> Option<Key> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        while let Some((modifier, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty())
+        {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+            rest = tail;
+        }
+        Some(Key::new(parse_code(rest)?, modifiers))
+    }
+}
+
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Key {
+        Key::new(code, KeyModifiers::NONE)
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(event: KeyEvent) -> Key {
+        Key::new(event.code, event.modifiers)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl"),
+            (KeyModifiers::ALT, "alt"),
+            (KeyModifiers::SHIFT, "shift"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        let name = match self.code {
+            KeyCode::Enter => "Enter",
+            KeyCode::Esc => "Esc",
+            KeyCode::Backspace => "Backspace",
+            KeyCode::Delete => "Delete",
+            KeyCode::Tab => "Tab",
+            KeyCode::BackTab => "BackTab",
+            KeyCode::Left => "Left",
+            KeyCode::Right => "Right",
+            KeyCode::Up => "Up",
+            KeyCode::Down => "Down",
+            KeyCode::Home => "Home",
+            KeyCode::End => "End",
+            KeyCode::PageUp => "PageUp",
+            KeyCode::PageDown => "PageDown",
+            KeyCode::Char(' ') => "Space",
+            KeyCode::Char(c) => return write!(f, "{}", c),
+            KeyCode::F(n) => return write!(f, "F{}", n),
+            _ => "Unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn parse_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let code = match name.to_ascii_lowercase().as_str() {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "space" => KeyCode::Char(' '),
+        other => match other.strip_prefix('f').map(str::parse) {
+            Some(Ok(n)) => KeyCode::F(n),
+            _ => return None,
+        },
+    };
+    Some(code)
+}
+
+/// Everything an operator can do with the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    OpenLaunchPanel,
+    NextTab,
+    PrevTab,
+    Up,
+    Down,
+    Submit,
+    Cancel,
+    Delete,
+    SetSource,
+    SetTarget,
+    EnterCoords,
+    FocusLogs,
+    PauseLogs,
+    FilterLogs,
+    SearchLogs,
+    ExportLogs,
+    /// Stop the launch countdown before T-0.
+    Abort,
+    /// Check or uncheck the selected checklist item.
+    ToggleItem,
+    PauseSimulation,
+    /// Advance the simulation by one tick, pausing it.
+    StepSimulation,
+    /// Switch to the next of `app::SPEEDS`.
+    CycleSpeed,
+}
+
+impl Action {
+    /// The name used in the config file.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::OpenLaunchPanel => "open_launch_panel",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Submit => "submit",
+            Action::Cancel => "cancel",
+            Action::Delete => "delete",
+            Action::SetSource => "set_source",
+            Action::SetTarget => "set_target",
+            Action::EnterCoords => "enter_coords",
+            Action::FocusLogs => "focus_logs",
+            Action::PauseLogs => "pause_logs",
+            Action::FilterLogs => "filter_logs",
+            Action::SearchLogs => "search_logs",
+            Action::ExportLogs => "export_logs",
+            Action::Abort => "abort",
+            Action::ToggleItem => "toggle_item",
+            Action::PauseSimulation => "pause_simulation",
+            Action::StepSimulation => "step_simulation",
+            Action::CycleSpeed => "cycle_speed",
+        }
+    }
+
+    /// Actions that edit a popup's text input and only apply while one is open.
+    pub fn is_text(self) -> bool {
+        matches!(self, Action::Submit | Action::Cancel | Action::Delete)
+    }
+}
+
+const DEFAULT_BINDINGS: [(Action, &[&str]); 22] = [
+    (Action::Quit, &["q"]),
+    (Action::OpenLaunchPanel, &["t"]),
+    (Action::NextTab, &["Right"]),
+    (Action::PrevTab, &["Left"]),
+    (Action::Up, &["Up"]),
+    (Action::Down, &["Down"]),
+    (Action::Submit, &["Enter"]),
+    (Action::Cancel, &["Esc"]),
+    (Action::Delete, &["Backspace", "Delete"]),
+    (Action::SetSource, &["s"]),
+    (Action::SetTarget, &["d"]),
+    (Action::EnterCoords, &["c"]),
+    (Action::FocusLogs, &["l"]),
+    (Action::PauseLogs, &["p"]),
+    (Action::FilterLogs, &["f"]),
+    (Action::SearchLogs, &["/"]),
+    (Action::ExportLogs, &["e"]),
+    (Action::Abort, &["a"]),
+    (Action::ToggleItem, &["Space"]),
+    (Action::PauseSimulation, &["z"]),
+    (Action::StepSimulation, &["n"]),
+    (Action::CycleSpeed, &["v"]),
+];
+
+/// Which keys trigger which action.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<Key>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap {
+            bindings: DEFAULT_BINDINGS
+                .iter()
+                .map(|(action, keys)| {
+                    (
+                        *action,
+                        keys.iter().filter_map(|key| Key::parse(key)).collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Replace the keys of `action`, the other actions keep theirs.
+    pub fn bind(&mut self, action: Action, keys: Vec<Key>) {
+        self.bindings.insert(action, keys);
+    }
+
+    /// Every action `key` is bound to, in declaration order.
+    pub fn actions(&self, key: Key) -> Vec<Action> {
+        DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, _)| *action)
+            .filter(|action| {
+                self.bindings
+                    .get(action)
+                    .is_some_and(|keys| keys.contains(&key))
+            })
+            .collect()
+    }
+
+    /// Keys bound to more than one action, with those actions.
+    pub fn conflicts(&self) -> Vec<(Key, Vec<Action>)> {
+        let mut keys: Vec<Key> = Vec::new();
+        for key in self.bindings.values().flatten() {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
+        }
+        keys.into_iter()
+            .map(|key| (key, self.actions(key)))
+            .filter(|(_, actions)| actions.len() > 1)
+            .collect()
+    }
+
+    /// How to show the first key of `action` in help texts, e.g. `<Enter>`.
+    pub fn label(&self, action: Action) -> String {
+        match self.bindings.get(&action).and_then(|keys| keys.first()) {
+            Some(key) => format!("<{}>", key),
+            None => "<unbound>".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_parse_back_from_their_display() {
+        for name in [
+            "Enter",
+            "Space",
+            "BackTab",
+            "PageDown",
+            "F12",
+            "q",
+            "Q",
+            "+",
+            "ctrl+q",
+            "alt+Left",
+            "ctrl+alt+x",
+            "shift+Up",
+        ] {
+            let key = Key::parse(name).unwrap();
+            assert_eq!(key.to_string(), name);
+            assert_eq!(Key::parse(&key.to_string()), Some(key));
+        }
+    }
+
+    #[test]
+    fn names_are_case_insensitive() {
+        assert_eq!(Key::parse("CTRL+ENTER"), Key::parse("ctrl+Enter"));
+        assert_eq!(Key::parse("pageup"), Some(KeyCode::PageUp.into()));
+        assert_eq!(Key::parse("f5"), Some(KeyCode::F(5).into()));
+    }
+
+    #[test]
+    fn shift_is_folded_into_characters() {
+        let shifted = Key::parse("shift+a").unwrap();
+        assert_eq!(shifted, KeyCode::Char('A').into());
+        assert_eq!(shifted.to_string(), "A");
+    }
+
+    #[test]
+    fn unknown_names_do_not_parse() {
+        for name in ["", "hyper+a", "ctrl+", "Return", "Fx", "ab"] {
+            assert_eq!(Key::parse(name), None, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn default_bindings_do_not_conflict() {
+        let keymap = Keymap::default();
+        assert!(keymap.conflicts().is_empty());
+        for (action, keys) in DEFAULT_BINDINGS {
+            assert_eq!(keymap.label(action), format!("<{}>", keys[0]));
+        }
+    }
+}
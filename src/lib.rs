@@ -0,0 +1,16 @@
+pub mod app;
+pub mod audit;
+pub mod auth;
+pub mod config;
+pub mod crossterm;
+pub mod geo;
+pub mod headless;
+pub mod keys;
+pub mod logs;
+pub mod record;
+pub mod scenario;
+pub mod signal;
+pub mod theme;
+pub mod timeline;
+pub mod traffic;
+pub mod ui;
@@ -0,0 +1,208 @@
+//! System messages and their export for reviewing a drill afterwards.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// Severity of a system message, from least to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LogLevel {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARNING",
+            LogLevel::Error => "ERROR",
+            LogLevel::Critical => "CRITICAL",
+        }
+    }
+}
+
+/// One line of the System Message panel.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LogEntry {
+    /// Simulated seconds since the session started.
+    pub time: f64,
+    pub level: LogLevel,
+    /// Name of the launcher the message is about.
+    pub source: Option<String>,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Simulated time as `hh:mm:ss`.
+    pub fn clock(&self) -> String {
+        let seconds = self.time as u64;
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    JsonLines,
+    Csv,
+}
+
+impl ExportFormat {
+    /// `.jsonl` or `.csv`, by the extension of `path`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<ExportFormat> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "jsonl" | "ndjson" => Some(ExportFormat::JsonLines),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Write `entries` oldest first, in the format the extension of `path` asks
+/// for.
+pub fn export<'a, P, I>(path: P, entries: I) -> io::Result<usize>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = &'a LogEntry>,
+{
+    let path = path.as_ref();
+    let format = ExportFormat::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{}: unsupported log format, expected .jsonl or .csv",
+                path.display()
+            ),
+        )
+    })?;
+    let mut out = BufWriter::new(File::create(path)?);
+    if format == ExportFormat::Csv {
+        writeln!(out, "time,clock,level,source,message")?;
+    }
+    let mut count = 0;
+    for entry in entries {
+        match format {
+            ExportFormat::JsonLines => {
+                let mut value = serde_json::to_value(entry)?;
+                value["clock"] = entry.clock().into();
+                writeln!(out, "{}", value)?;
+            }
+            ExportFormat::Csv => writeln!(
+                out,
+                "{},{},{},{},{}",
+                entry.time,
+                entry.clock(),
+                entry.level.as_str(),
+                csv_field(entry.source.as_deref().unwrap_or_default()),
+                csv_field(&entry.message)
+            )?,
+        }
+        count += 1;
+    }
+    out.flush()?;
+    Ok(count)
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, path::PathBuf, process};
+
+    fn entries() -> Vec<LogEntry> {
+        vec![
+            LogEntry {
+                time: 0.5,
+                level: LogLevel::Info,
+                source: None,
+                message: "System check complete".to_string(),
+            },
+            LogEntry {
+                time: 3725.0,
+                level: LogLevel::Critical,
+                source: Some("USA-1".to_string()),
+                message: "Launch from USA-1, \"drill\"\nT-10s".to_string(),
+            },
+        ]
+    }
+
+    /// Export `entries()` to a file of its own and read it back.
+    fn exported(name: &str) -> String {
+        let path: PathBuf = env::temp_dir().join(format!("logs-{}-{}", process::id(), name));
+        assert_eq!(export(&path, &entries()).unwrap(), 2);
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        text
+    }
+
+    #[test]
+    fn plain_fields_are_left_alone() {
+        assert_eq!(csv_field("USA-1"), "USA-1");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn fields_with_separators_quotes_or_line_breaks_are_quoted() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"go\""), "\"say \"\"go\"\"\"");
+        assert_eq!(csv_field("one\ntwo"), "\"one\ntwo\"");
+        assert_eq!(csv_field("one\r\ntwo"), "\"one\r\ntwo\"");
+    }
+
+    #[test]
+    fn csv_has_a_header_and_a_row_per_entry() {
+        assert_eq!(
+            exported("export.csv"),
+            "time,clock,level,source,message\n\
+             0.5,00:00:00,INFO,,System check complete\n\
+             3725,01:02:05,CRITICAL,USA-1,\"Launch from USA-1, \"\"drill\"\"\nT-10s\"\n"
+        );
+    }
+
+    #[test]
+    fn json_lines_have_an_object_per_entry() {
+        let text = exported("export.jsonl");
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["source"], serde_json::Value::Null);
+        assert_eq!(lines[1]["clock"], "01:02:05");
+        assert_eq!(lines[1]["message"], entries()[1].message);
+    }
+
+    #[test]
+    fn the_extension_picks_the_format() {
+        assert_eq!(ExportFormat::from_path("a.CSV"), Some(ExportFormat::Csv));
+        assert_eq!(
+            ExportFormat::from_path("a.ndjson"),
+            Some(ExportFormat::JsonLines)
+        );
+        assert_eq!(ExportFormat::from_path("a.txt"), None);
+        assert_eq!(ExportFormat::from_path("csv"), None);
+        let err = export("logs.txt", &entries()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -1,21 +1,490 @@
-mod app;
-mod crossterm;
-mod ui;
-
-use crate::crossterm::run;
 use argh::FromArgs;
-use std::error::Error;
+use missile_launcher::{
+    app::App,
+    audit::{self, AuditLog},
+    auth::{Authorization, CodeHash},
+    config::Config,
+    crossterm::{run, Session},
+    headless::{self, Step},
+    logs::ExportFormat,
+    record::InputLog,
+    scenario::Scenario,
+    theme::{Theme, PRESETS},
+    ui::FULL_SIZE,
+};
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+const CODE_HASH_ENV: &str = "MISSILE_LAUNCHER_CODE_HASH";
+const SECOND_CODE_HASH_ENV: &str = "MISSILE_LAUNCHER_SECOND_CODE_HASH";
+const NO_COLOR_ENV: &str = "NO_COLOR";
 
-/// Missile Launcher
+/// Missile Launcher, the options go before the subcommand
 #[derive(Debug, FromArgs)]
 struct Cli {
-    /// correct code to launch missile
+    /// correct code to launch missile, prefer --code-hash as this shows up in `ps`
     #[argh(option, default = "String::from(\"NTNUCSIE\")")]
     code: String,
+
+    /// salted hash of the launch code (see --hash-code), also read from
+    /// MISSILE_LAUNCHER_CODE_HASH
+    #[argh(option)]
+    code_hash: Option<String>,
+
+    /// code of the second operator, enables the two-person rule
+    #[argh(option)]
+    second_code: Option<String>,
+
+    /// salted hash of the second operator's code, also read from
+    /// MISSILE_LAUNCHER_SECOND_CODE_HASH
+    #[argh(option)]
+    second_code_hash: Option<String>,
+
+    /// file with one code hash per operator and line, overrides the options above
+    #[argh(option)]
+    code_hash_file: Option<String>,
+
+    /// read a code from stdin, print its salted hash and exit
+    #[argh(switch)]
+    hash_code: bool,
+
+    /// seconds the second operator has to confirm after the first one
+    #[argh(option, default = "30")]
+    auth_window: u64,
+
+    /// failed attempts before the launch panel locks
+    #[argh(option, default = "3")]
+    max_attempts: u32,
+
+    /// seconds the first lockout lasts, doubling with every further one
+    #[argh(option, default = "30")]
+    lockout: u64,
+
+    /// seconds from the last accepted code to lift-off, during which the
+    /// launch can be aborted
+    #[argh(option, default = "10")]
+    countdown: u64,
+
+    /// real milliseconds a simulation tick takes at 1x speed, each tick being
+    /// 100 ms of simulated time
+    #[argh(option, default = "100")]
+    tick_ms: u64,
+
+    /// frames drawn per second
+    #[argh(option, default = "30")]
+    fps: u32,
+
+    /// seed for the simulated signals, the same seed replays the same session
+    #[argh(option)]
+    seed: Option<u64>,
+
+    /// record the session to an asciicast v2 file
+    #[argh(option)]
+    record: Option<PathBuf>,
+
+    /// replay the inputs of a session recorded with --record
+    #[argh(option)]
+    replay: Option<PathBuf>,
+
+    /// scenario file (.toml or .json) describing launchers, logs and packets
+    #[argh(option)]
+    scenario: Option<String>,
+
+    /// config file (.toml) with key bindings and theme
+    #[argh(option)]
+    config: Option<String>,
+
+    /// write the session's log to this .jsonl or .csv file on exit
+    #[argh(option)]
+    log_export: Option<PathBuf>,
+
+    /// color theme, a preset (default, amber-crt, green-phosphor,
+    /// high-contrast, monochrome) or the path of a theme file
+    #[argh(option)]
+    theme: Option<String>,
+
+    /// append every operator action to this hash-chained audit trail
+    #[argh(option)]
+    audit: Option<PathBuf>,
+
+    #[argh(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Run(RunCommand),
+    Validate(ValidateCommand),
+    List(ListCommand),
+    Render(RenderCommand),
+    Audit(AuditCommand),
+}
+
+/// Start the dashboard, the same as without a subcommand
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "run")]
+struct RunCommand {}
+
+/// Check the files given with --scenario, --config, --theme and
+/// --code-hash-file and print what is wrong with them
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "validate")]
+struct ValidateCommand {}
+
+/// Print the launchers of the scenario
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "list")]
+struct ListCommand {
+    /// print JSON instead of a table
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Print one frame of the dashboard without a terminal
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "render")]
+struct RenderCommand {
+    /// tab to show, 1 for System Monitor and 2 for Launch Missile
+    #[argh(option, default = "1")]
+    tab: usize,
+
+    /// ticks to simulate before the frame is drawn
+    #[argh(option, default = "0")]
+    ticks: u64,
+
+    /// width of the frame in columns
+    #[argh(option, default = "FULL_SIZE.0")]
+    width: u16,
+
+    /// height of the frame in rows
+    #[argh(option, default = "FULL_SIZE.1")]
+    height: u16,
+
+    /// keep the colors as ANSI escape sequences
+    #[argh(switch)]
+    ansi: bool,
+}
+
+/// Inspect an audit trail written with --audit
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "audit")]
+struct AuditCommand {
+    #[argh(subcommand)]
+    command: AuditSubcommand,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+enum AuditSubcommand {
+    Verify(VerifyCommand),
+}
+
+/// Check that no line of an audit trail was changed, inserted or removed
+#[derive(Debug, FromArgs)]
+#[argh(subcommand, name = "verify")]
+struct VerifyCommand {
+    /// the audit trail
+    #[argh(positional)]
+    file: PathBuf,
+}
+
+/// Print the records checked or every broken link, and exit with 1 when
+/// the trail was tampered with.
+fn verify_audit(path: &Path) {
+    let verification = audit::verify(path).unwrap_or_else(|err| {
+        eprintln!("{}: {}", path.display(), err);
+        process::exit(1);
+    });
+    if verification.is_intact() {
+        println!(
+            "{}: {} records, chain intact",
+            path.display(),
+            verification.records
+        );
+        return;
+    }
+    for problem in &verification.problems {
+        eprintln!("{}: {}", path.display(), problem);
+    }
+    process::exit(1);
+}
+
+/// Load every file given in the options, printing `ok` or its problems,
+/// and exit with 1 when one of them is invalid.
+fn validate(args: &Cli) {
+    let mut checked = 0;
+    let mut valid = true;
+    let mut report = |path: &str, result: Result<(), String>| {
+        checked += 1;
+        match result {
+            Ok(()) => println!("{}: ok", path),
+            Err(err) => {
+                valid = false;
+                eprintln!("{}: {}", path, err);
+            }
+        }
+    };
+    if let Some(path) = &args.scenario {
+        report(
+            path,
+            Scenario::load(path)
+                .map(drop)
+                .map_err(|err| err.to_string()),
+        );
+    }
+    let config = match &args.config {
+        Some(path) => {
+            let config = Config::load(path);
+            let result = config.as_ref().map(drop).map_err(|err| err.to_string());
+            report(path, result);
+            config.unwrap_or_default()
+        }
+        None => Config::default(),
+    };
+    if let Some(name) = args.theme.as_ref().or(config.theme.as_ref()) {
+        report(
+            name,
+            Theme::resolve(name)
+                .map(drop)
+                .map_err(|err| err.to_string()),
+        );
+    }
+    if let Some(path) = &args.code_hash_file {
+        report(
+            path,
+            operator_codes(args)
+                .map(drop)
+                .map_err(|err| err.to_string()),
+        );
+    }
+    if checked == 0 {
+        eprintln!("nothing to validate, pass --scenario, --config, --theme or --code-hash-file");
+        process::exit(1);
+    }
+    if !valid {
+        process::exit(1);
+    }
+}
+
+/// Print the launchers as an aligned table, or as JSON in the format
+/// scenario files use.
+fn list_launchers(scenario: &Scenario, json: bool) -> io::Result<()> {
+    if json {
+        let json = serde_json::to_string_pretty(&scenario.launchers)?;
+        return print_out(&format!("{}\n", json));
+    }
+    let width = scenario
+        .launchers
+        .iter()
+        .map(|launcher| launcher.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("NAME".len());
+    let mut table = format!(
+        "{:<width$}  {:<8}  {:>9}  {:>10}  STATE\n",
+        "NAME", "LOCATION", "LATITUDE", "LONGITUDE"
+    );
+    for launcher in &scenario.launchers {
+        let (lat, lon) = launcher.coords;
+        table.push_str(&format!(
+            "{:<width$}  {:<8}  {:>9.4}  {:>10.4}  {}\n",
+            launcher.name,
+            launcher.location,
+            lat,
+            lon,
+            launcher.state.as_str()
+        ));
+    }
+    print_out(&table)
+}
+
+fn render_frame(mut app: App, args: &RenderCommand) -> io::Result<()> {
+    if !(1..=app.tabs.titles.len()).contains(&args.tab) {
+        eprintln!(
+            "--tab must be between 1 and {}, got {}",
+            app.tabs.titles.len(),
+            args.tab
+        );
+        process::exit(1);
+    }
+    app.tabs.index = args.tab - 1;
+    let buffer = headless::render(app, args.width, args.height, &[Step::Ticks(args.ticks)])?;
+    if args.ansi {
+        print_out(&headless::to_ansi(&buffer))
+    } else {
+        print_out(&headless::to_text(&buffer))
+    }
+}
+
+/// Write to stdout, stopping quietly when it is piped into something like
+/// `head` that exits early.
+fn print_out(text: &str) -> io::Result<()> {
+    match io::stdout().lock().write_all(text.as_bytes()) {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// Resolve the operator codes from the hash file, the hash options and
+/// environment variables, or the plaintext options, in that order.
+fn operator_codes(args: &Cli) -> Result<Vec<CodeHash>, Box<dyn Error>> {
+    if let Some(path) = &args.code_hash_file {
+        let codes = fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(CodeHash::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if codes.is_empty() {
+            return Err(format!("{} does not contain any code hash", path).into());
+        }
+        return Ok(codes);
+    }
+
+    let first = match args
+        .code_hash
+        .clone()
+        .or_else(|| env::var(CODE_HASH_ENV).ok())
+    {
+        Some(hash) => CodeHash::parse(&hash)?,
+        None => CodeHash::new(&args.code),
+    };
+    let second = match args
+        .second_code_hash
+        .clone()
+        .or_else(|| env::var(SECOND_CODE_HASH_ENV).ok())
+    {
+        Some(hash) => Some(CodeHash::parse(&hash)?),
+        None => match &args.second_code {
+            Some(code) if first.verify(code) => {
+                return Err("--second-code must differ from --code".into())
+            }
+            Some(code) => Some(CodeHash::new(code)),
+            None => None,
+        },
+    };
+    Ok(std::iter::once(first).chain(second).collect())
+}
+
+/// Pick the theme from --theme, then NO_COLOR, then the config file.
+fn theme(args: &Cli, config: &Config) -> Result<Theme, Box<dyn Error>> {
+    let no_color = env::var_os(NO_COLOR_ENV).is_some_and(|value| !value.is_empty());
+    let name = match (&args.theme, &config.theme) {
+        (Some(name), _) => name,
+        _ if no_color => return Ok(Theme::monochrome()),
+        (None, Some(name)) => name,
+        (None, None) => return Ok(Theme::default()),
+    };
+    Theme::resolve(name).map_err(|err| {
+        let presets = PRESETS.join(", ");
+        format!("{}: {} (presets are {})", name, err, presets).into()
+    })
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     let args: Cli = argh::from_env();
-    run(args.code)?;
+
+    match &args.command {
+        Some(Command::Audit(AuditCommand {
+            command: AuditSubcommand::Verify(verify),
+        })) => {
+            verify_audit(&verify.file);
+            return Ok(());
+        }
+        Some(Command::Validate(_)) => {
+            validate(&args);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    if args.hash_code {
+        let mut code = String::new();
+        io::stdin().read_line(&mut code)?;
+        println!("{}", CodeHash::new(code.trim_end_matches(['\r', '\n'])));
+        return Ok(());
+    }
+
+    let scenario = match &args.scenario {
+        Some(path) => Scenario::load(path).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path, err);
+            process::exit(1);
+        }),
+        None => Scenario::default(),
+    };
+    if let Some(Command::List(list)) = &args.command {
+        list_launchers(&scenario, list.json)?;
+        return Ok(());
+    }
+
+    let config = match &args.config {
+        Some(path) => Config::load(path).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path, err);
+            process::exit(1);
+        }),
+        None => Config::default(),
+    };
+
+    if args.tick_ms == 0 || args.fps == 0 {
+        eprintln!("--tick-ms and --fps must be at least 1");
+        process::exit(1);
+    }
+
+    if let Some(path) = &args.log_export {
+        if ExportFormat::from_path(path).is_none() {
+            eprintln!("{}: expected a .jsonl or .csv file", path.display());
+            process::exit(1);
+        }
+    }
+
+    let codes = operator_codes(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    let authorization = Authorization::new(codes, Duration::from_secs(args.auth_window))
+        .with_lockout(args.max_attempts, Duration::from_secs(args.lockout));
+
+    let replay = args.replay.as_ref().map(|path| {
+        InputLog::load(path).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path.display(), err);
+            process::exit(1);
+        })
+    });
+    let seed = args
+        .seed
+        .or_else(|| replay.as_ref().and_then(|log| log.seed))
+        .unwrap_or_else(rand::random);
+    let mut app = App::new("Missile Launcher", authorization, &scenario, seed);
+    app.keymap = config.keymap()?;
+    app.log_export = args.log_export.clone();
+    app.countdown = Duration::from_secs(args.countdown);
+    app.tick_rate = Duration::from_millis(args.tick_ms);
+    if let Some(path) = &args.audit {
+        app.audit = Some(AuditLog::open(path).unwrap_or_else(|err| {
+            eprintln!("{}: {}", path.display(), err);
+            process::exit(1);
+        }));
+    }
+    app.theme = theme(&args, &config).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+    if let Some(Command::Render(render)) = &args.command {
+        render_frame(app, render)?;
+        return Ok(());
+    }
+    let session = Session {
+        record: args.record,
+        replay,
+    };
+    run(app, Duration::from_secs(1) / args.fps, session)?;
     Ok(())
 }
@@ -0,0 +1,361 @@
+//! Session recording as asciicast v2 (https://docs.asciinema.org/manual/asciicast/v2/).
+//!
+//! Every event is stamped with the wall clock time since the recording
+//! started, so the recording plays back as it happened whatever the speed of
+//! the simulation. Inputs also carry the tick they arrived on, as in
+//! `"42 Enter"`, so a replay feeds them to the app on the exact same tick.
+
+use crate::{headless::sgr, keys::Key};
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use serde_json::{json, Value};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+use tui::buffer::Buffer;
+
+const SEED_ENV: &str = "MISSILE_LAUNCHER_SEED";
+
+/// A key press or a mouse event the dashboard reacts to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Key(Key),
+    Mouse(MouseEvent),
+}
+
+impl Input {
+    /// `Enter`, `ctrl+q` or `mouse click 12 5`, `None` for inputs that are
+    /// not worth recording.
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Input::Key(key) => {
+                let name = key.to_string();
+                Key::parse(&name).map(|_| name)
+            }
+            Input::Mouse(event) => {
+                let kind = match event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => "click",
+                    MouseEventKind::ScrollUp => "scroll-up",
+                    MouseEventKind::ScrollDown => "scroll-down",
+                    _ => return None,
+                };
+                Some(format!("mouse {} {} {}", kind, event.column, event.row))
+            }
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Input> {
+        let mut parts = match name.strip_prefix("mouse ") {
+            Some(mouse) => mouse.split(' '),
+            None => return Key::parse(name).map(Input::Key),
+        };
+        let kind = match parts.next()? {
+            "click" => MouseEventKind::Down(MouseButton::Left),
+            "scroll-up" => MouseEventKind::ScrollUp,
+            "scroll-down" => MouseEventKind::ScrollDown,
+            _ => return None,
+        };
+        let column = parts.next()?.parse().ok()?;
+        let row = parts.next()?.parse().ok()?;
+        Some(Input::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }))
+    }
+}
+
+pub struct Recorder {
+    out: BufWriter<File>,
+    previous: Option<Buffer>,
+    started: Instant,
+}
+
+impl Recorder {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        width: u16,
+        height: u16,
+        seed: u64,
+    ) -> io::Result<Recorder> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": timestamp,
+            "title": "Missile Launcher",
+            "env": { SEED_ENV: seed.to_string() },
+        });
+        writeln!(out, "{}", header)?;
+        Ok(Recorder {
+            out,
+            previous: None,
+            started: Instant::now(),
+        })
+    }
+
+    /// Write the cells that changed since the last frame.
+    pub fn frame(&mut self, buffer: &Buffer) -> io::Result<()> {
+        let mut data = String::new();
+        let mut cursor = None;
+        let mut style = None;
+        let updates = match &self.previous {
+            Some(previous) if previous.area == buffer.area => previous.diff(buffer),
+            _ => {
+                data.push_str("\x1b[2J");
+                Buffer::empty(buffer.area).diff(buffer)
+            }
+        };
+        for (x, y, cell) in updates {
+            if cursor != Some((x, y)) {
+                data.push_str(&format!("\x1b[{};{}H", y + 1, x + 1));
+            }
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                data.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(current);
+            }
+            data.push_str(&cell.symbol);
+            cursor = Some((x + 1, y));
+        }
+        self.previous = Some(buffer.clone());
+        if data.is_empty() {
+            return Ok(());
+        }
+        data.push_str("\x1b[0m");
+        self.event("o", &data)
+    }
+
+    /// Write an input that arrived after `tick` ticks, skipping those that
+    /// could not be replayed.
+    pub fn input(&mut self, tick: u64, input: Input) -> io::Result<()> {
+        match input.name() {
+            Some(name) => self.event("i", &format!("{} {}", tick, name)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        self.event("r", &format!("{}x{}", width, height))
+    }
+
+    fn event(&mut self, kind: &str, data: &str) -> io::Result<()> {
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(self.out, "{}", json!([time, kind, data]))
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// The inputs of a recorded session, each with the tick it arrived after.
+pub struct InputLog {
+    pub seed: Option<u64>,
+    pub inputs: VecDeque<(u64, Input)>,
+}
+
+impl InputLog {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<InputLog> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header: Value = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).map_err(|err| invalid(err.to_string()))?,
+            None => return Err(invalid("empty recording".to_string())),
+        };
+        if header["version"] != 2 {
+            return Err(invalid("not an asciicast v2 recording".to_string()));
+        }
+        let seed = header["env"][SEED_ENV]
+            .as_str()
+            .and_then(|seed| seed.parse().ok());
+
+        let mut inputs = VecDeque::new();
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: Value = serde_json::from_str(&line)
+                .map_err(|err| invalid(format!("event {}: {}", i + 1, err)))?;
+            if event[1] != "i" {
+                continue;
+            }
+            let (tick, name) = event[2]
+                .as_str()
+                .and_then(|data| data.split_once(' '))
+                .and_then(|(tick, name)| Some((tick.parse().ok()?, name)))
+                .ok_or_else(|| invalid(format!("event {}: malformed", i + 1)))?;
+            let input = Input::parse(name)
+                .ok_or_else(|| invalid(format!("event {}: unknown input", i + 1)))?;
+            inputs.push_back((tick, input));
+        }
+        Ok(InputLog { seed, inputs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyCode;
+    use std::{env, fs, path::PathBuf, process};
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Input {
+        Input::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        })
+    }
+
+    /// A recording with `events` after its header, in a file of its own.
+    fn cast(name: &str, events: &[&str]) -> PathBuf {
+        let path = env::temp_dir().join(format!("record-{}-{}.cast", process::id(), name));
+        let header =
+            r#"{"version": 2, "width": 100, "height": 30, "env": {"MISSILE_LAUNCHER_SEED": "7"}}"#;
+        let lines: Vec<&str> = std::iter::once(header)
+            .chain(events.iter().copied())
+            .collect();
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+        path
+    }
+
+    fn load(path: &Path) -> io::Result<InputLog> {
+        let log = InputLog::load(path);
+        fs::remove_file(path).unwrap();
+        log
+    }
+
+    #[test]
+    fn inputs_parse_back_from_their_names() {
+        for input in [
+            Input::Key(KeyCode::Enter.into()),
+            Input::Key(KeyCode::Char(' ').into()),
+            Input::Key(KeyCode::F(5).into()),
+            Input::Key(Key::new(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            Input::Key(Key::new(KeyCode::Char('a'), KeyModifiers::SHIFT)),
+            Input::Key(Key::new(KeyCode::Left, KeyModifiers::ALT)),
+            mouse(MouseEventKind::Down(MouseButton::Left), 12, 5),
+            mouse(MouseEventKind::ScrollUp, 0, 0),
+            mouse(MouseEventKind::ScrollDown, 99, 29),
+        ] {
+            let name = input.name().unwrap();
+            assert_eq!(Input::parse(&name), Some(input), "{}", name);
+        }
+    }
+
+    #[test]
+    fn inputs_that_cannot_be_replayed_have_no_name() {
+        assert_eq!(Input::Key(KeyCode::Insert.into()).name(), None);
+        assert_eq!(mouse(MouseEventKind::Moved, 1, 1).name(), None);
+        assert_eq!(
+            mouse(MouseEventKind::Up(MouseButton::Left), 1, 1).name(),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_names_do_not_parse() {
+        for name in [
+            "",
+            "hyper+a",
+            "mouse",
+            "mouse drag 1 2",
+            "mouse click 1",
+            "mouse click x 2",
+        ] {
+            assert_eq!(Input::parse(name), None, "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn recorded_inputs_load_with_their_ticks() {
+        let path = env::temp_dir().join(format!("record-{}-recorded.cast", process::id()));
+        let mut recorder = Recorder::create(&path, 100, 30, 7).unwrap();
+        recorder
+            .frame(&Buffer::empty(tui::layout::Rect::new(0, 0, 4, 2)))
+            .unwrap();
+        recorder
+            .input(0, Input::Key(KeyCode::Right.into()))
+            .unwrap();
+        recorder.resize(120, 40).unwrap();
+        recorder
+            .input(0, Input::Key(KeyCode::Char('s').into()))
+            .unwrap();
+        let click = mouse(MouseEventKind::Down(MouseButton::Left), 3, 4);
+        recorder.input(42, click).unwrap();
+        recorder
+            .input(43, Input::Key(KeyCode::Insert.into()))
+            .unwrap();
+        recorder.flush().unwrap();
+
+        let log = load(&path).unwrap();
+        assert_eq!(log.seed, Some(7));
+        assert_eq!(
+            Vec::from(log.inputs),
+            [
+                (0, Input::Key(KeyCode::Right.into())),
+                (0, Input::Key(KeyCode::Char('s').into())),
+                (42, click),
+            ]
+        );
+    }
+
+    #[test]
+    fn inputs_without_a_tick_are_rejected() {
+        for (name, event, problem) in [
+            ("untagged", r#"[0.5, "i", "Enter"]"#, "event 1: malformed"),
+            ("tick", r#"[0.5, "i", "x Enter"]"#, "event 1: malformed"),
+            (
+                "negative",
+                r#"[0.5, "i", "-1 Enter"]"#,
+                "event 1: malformed",
+            ),
+            ("data", r#"[0.5, "i", 3]"#, "event 1: malformed"),
+            (
+                "unknown",
+                r#"[0.5, "i", "3 hyper+a"]"#,
+                "event 1: unknown input",
+            ),
+            ("json", r#"[0.5, "i""#, "event 1: "),
+        ] {
+            let path = cast(name, &[event]);
+            let err = load(&path).err().unwrap().to_string();
+            assert!(err.starts_with(problem), "{}: {}", name, err);
+        }
+    }
+
+    #[test]
+    fn only_asciicast_v2_loads() {
+        let path = env::temp_dir().join(format!("record-{}-v1.cast", process::id()));
+        fs::write(&path, "{\"version\": 1}\n").unwrap();
+        let err = load(&path).err().unwrap().to_string();
+        assert_eq!(err, "not an asciicast v2 recording");
+
+        fs::write(&path, "").unwrap();
+        let err = load(&path).err().unwrap().to_string();
+        assert_eq!(err, "empty recording");
+    }
+
+    #[test]
+    fn output_and_blank_lines_are_skipped() {
+        let path = cast(
+            "skipped",
+            &[r#"[0.1, "o", "hello"]"#, "", r#"[0.2, "i", "5 q"]"#],
+        );
+        let log = load(&path).unwrap();
+        assert_eq!(
+            Vec::from(log.inputs),
+            [(5, Input::Key(KeyCode::Char('q').into()))]
+        );
+    }
+}
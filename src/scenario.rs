@@ -0,0 +1,798 @@
+use crate::theme::parse_color;
+use crate::{
+    app::{Launcher, LauncherState},
+    logs::LogLevel,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashSet, error::Error, f64::consts::TAU, fmt, fs, io, path::Path, time::Duration,
+};
+
+const LOGS: [(&str, LogLevel); 47] = [
+    ("TPE launch system is ready and stable", LogLevel::Info),
+    ("LAX launch system is ready and stable", LogLevel::Info),
+    ("JFK launch system is ready and stable", LogLevel::Info),
+    (
+        "Unknown connection detected from 140.122.64.120 (Taiwan)",
+        LogLevel::Warning,
+    ),
+    ("SFO launch system is ready and stable", LogLevel::Info),
+    ("ORD launch system is ready and stable", LogLevel::Info),
+    ("DFW launch system is ready and stable", LogLevel::Info),
+    (
+        "Lost connection from LAX launch system due to internal errors",
+        LogLevel::Error,
+    ),
+    ("MIA launch system is ready and stable", LogLevel::Info),
+    ("SEA launch system is ready and stable", LogLevel::Info),
+    (
+        "Connection established with ISS (International Space Station)",
+        LogLevel::Info,
+    ),
+    (
+        "Received telemetry data from TPE launch system",
+        LogLevel::Info,
+    ),
+    (
+        "Error reading sensor data from JFK launch system",
+        LogLevel::Error,
+    ),
+    (
+        "Power outage detected at LAX launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Authentication failure from 192.168.0.1 (internal network)",
+        LogLevel::Warning,
+    ),
+    (
+        "Launch countdown initiated for ORD launch system",
+        LogLevel::Info,
+    ),
+    (
+        "Critical system update installed on DFW launch system",
+        LogLevel::Info,
+    ),
+    (
+        "Network congestion observed on SFO launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Data corruption detected in MIA launch system logs",
+        LogLevel::Error,
+    ),
+    (
+        "Launch sequence aborted for SEA launch system",
+        LogLevel::Critical,
+    ),
+    ("System overload on TPE launch system", LogLevel::Warning),
+    (
+        "Launch system reconfigured successfully at JFK",
+        LogLevel::Info,
+    ),
+    (
+        "Unauthorized access attempt detected on ORD launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Emergency shutdown triggered on LAX launch system",
+        LogLevel::Critical,
+    ),
+    (
+        "Connection established with weather satellite",
+        LogLevel::Info,
+    ),
+    (
+        "Error writing log file on DFW launch system",
+        LogLevel::Error,
+    ),
+    (
+        "Unexpected response received from SFO launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Insufficient fuel detected in MIA launch system",
+        LogLevel::Error,
+    ),
+    ("Launch system rebooted successfully at SEA", LogLevel::Info),
+    (
+        "Unauthorized login attempt from 192.168.0.10 (internal network)",
+        LogLevel::Warning,
+    ),
+    (
+        "Critical hardware failure reported by LAX launch system",
+        LogLevel::Critical,
+    ),
+    (
+        "Security breach detected on SFO launch system",
+        LogLevel::Critical,
+    ),
+    ("Power supply failure on DFW launch system", LogLevel::Error),
+    (
+        "Communication error with satellite at MIA launch system",
+        LogLevel::Error,
+    ),
+    (
+        "Launch aborted due to inclement weather at SEA launch system",
+        LogLevel::Critical,
+    ),
+    (
+        "Unauthorized access attempt from external IP 203.120.45.78",
+        LogLevel::Warning,
+    ),
+    (
+        "Critical software bug discovered on TPE launch system",
+        LogLevel::Critical,
+    ),
+    (
+        "Sensor malfunction detected on JFK launch system",
+        LogLevel::Error,
+    ),
+    (
+        "Network outage affecting ORD launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Launch system initialization completed at LAX",
+        LogLevel::Info,
+    ),
+    (
+        "Unauthorized login detected on DFW launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Satellite signal loss experienced on SFO launch system",
+        LogLevel::Warning,
+    ),
+    ("Fuel leak detected in MIA launch system", LogLevel::Error),
+    (
+        "Launch system update deployed successfully at SEA",
+        LogLevel::Info,
+    ),
+    (
+        "Unauthorized network scan detected on TPE launch system",
+        LogLevel::Warning,
+    ),
+    (
+        "Critical failure in propulsion system on JFK launch system",
+        LogLevel::Critical,
+    ),
+    (
+        "Error in communication protocol with ISS on ORD launch system",
+        LogLevel::Error,
+    ),
+];
+
+/// Packets per second of the built-in sites, the others send
+/// `traffic::DEFAULT_RATE`.
+const PACKETS: [(&str, u64); 10] = [
+    ("TPE", 9),
+    ("LAX", 5),
+    ("JFK", 8),
+    ("SFO", 3),
+    ("ORD", 4),
+    ("DFW", 7),
+    ("MIA", 2),
+    ("SEA", 1),
+    ("CLT", 10),
+    ("LAS", 7),
+];
+
+const LAUNCHERS: [(&str, &str, (f64, f64), LauncherState); 34] = [
+    (
+        "Asia-1",
+        "TPE",
+        (25.0094715, 121.5370432),
+        LauncherState::Ready,
+    ),
+    (
+        "USA-1",
+        "LAX",
+        (34.052235, -118.243683),
+        LauncherState::Ready,
+    ),
+    (
+        "USA-2",
+        "JFK",
+        (40.6413111, -73.7781391),
+        LauncherState::Offline,
+    ),
+    ("USA-3", "SFO", (37.7749, -122.4194), LauncherState::Ready),
+    ("USA-4", "ORD", (41.9742, -87.9073), LauncherState::Offline),
+    ("USA-5", "DFW", (32.8998, -97.0403), LauncherState::Ready),
+    ("USA-6", "MIA", (25.7617, -80.1918), LauncherState::Offline),
+    ("USA-7", "SEA", (47.6062, -122.3321), LauncherState::Ready),
+    ("USA-8", "CLT", (35.2271, -80.8431), LauncherState::Offline),
+    ("USA-9", "LAS", (36.1699, -115.1398), LauncherState::Ready),
+    ("Europe-1", "LHR", (51.5074, -0.1278), LauncherState::Ready),
+    ("Europe-2", "CDG", (48.8566, 2.3522), LauncherState::Offline),
+    ("Asia-2", "HND", (35.6895, 139.6917), LauncherState::Ready),
+    ("Asia-3", "ICN", (37.5665, 126.9780), LauncherState::Offline),
+    ("Africa-1", "JNB", (-26.2041, 28.0473), LauncherState::Ready),
+    (
+        "Africa-2",
+        "CAI",
+        (30.0444, 31.2357),
+        LauncherState::Offline,
+    ),
+    (
+        "Australia-1",
+        "SYD",
+        (-33.8688, 151.2093),
+        LauncherState::Ready,
+    ),
+    (
+        "SouthAmerica-1",
+        "GRU",
+        (-23.5505, -46.6333),
+        LauncherState::Ready,
+    ),
+    ("Europe-3", "FRA", (50.1109, 8.6821), LauncherState::Ready),
+    ("Europe-4", "MAD", (40.4168, -3.7038), LauncherState::Ready),
+    ("Asia-4", "PVG", (31.2304, 121.4737), LauncherState::Ready),
+    ("Asia-5", "BOM", (19.0760, 72.8777), LauncherState::Offline),
+    ("Africa-3", "LOS", (6.5244, 3.3792), LauncherState::Ready),
+    (
+        "Africa-4",
+        "NBO",
+        (-1.2864, 36.8172),
+        LauncherState::Offline,
+    ),
+    (
+        "Australia-2",
+        "MEL",
+        (-37.8136, 144.9631),
+        LauncherState::Offline,
+    ),
+    (
+        "SouthAmerica-2",
+        "EZE",
+        (-34.6037, -58.3816),
+        LauncherState::Offline,
+    ),
+    ("Europe-5", "AMS", (52.3676, 4.9041), LauncherState::Ready),
+    (
+        "Europe-6",
+        "FCO",
+        (41.9028, 12.4964),
+        LauncherState::Offline,
+    ),
+    ("Asia-6", "SIN", (1.3521, 103.8198), LauncherState::Ready),
+    ("Asia-7", "BKK", (13.7563, 100.5018), LauncherState::Offline),
+    ("Africa-5", "CPT", (-33.9249, 18.4241), LauncherState::Ready),
+    ("Africa-6", "ALG", (36.7372, 3.0865), LauncherState::Ready),
+    (
+        "Australia-3",
+        "BNE",
+        (-27.4698, 153.0251),
+        LauncherState::Ready,
+    ),
+    (
+        "SouthAmerica-3",
+        "LIM",
+        (-12.0464, -77.0428),
+        LauncherState::Offline,
+    ),
+];
+
+const CHECKLIST: [(&str, bool); 5] = [
+    ("Authenticate the launch order", true),
+    ("Confirm the target with command", true),
+    ("Check the weather at the launch site", false),
+    ("Clear the launch area", true),
+    ("Notify allied early warning", false),
+];
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChecklistItem {
+    pub label: String,
+    /// The launch panel stays shut until the item is checked.
+    #[serde(default)]
+    pub mandatory: bool,
+    #[serde(skip)]
+    pub checked: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogMessage {
+    pub message: String,
+    pub level: LogLevel,
+}
+
+/// Highest DEFCON level, the calmest.
+pub const DEFCON_PEACE: u8 = 5;
+
+/// Something that happens `at` a set time of the session. One event may do
+/// any of: put a launcher into a state, log a message and change the DEFCON
+/// level, in that order.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimelineEvent {
+    /// Simulated time as `mm:ss` or `hh:mm:ss`, see `parse_time`.
+    pub at: String,
+    /// Name or location of the launchers to put into `state`.
+    pub launcher: Option<String>,
+    pub state: Option<LauncherState>,
+    pub log: Option<LogMessage>,
+    /// From 5, peace, to 1, war.
+    pub defcon: Option<u8>,
+}
+
+/// Parse `mm:ss` or `hh:mm:ss`, with optional fractions of a second.
+pub fn parse_time(text: &str) -> Option<Duration> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [minutes, seconds] => ("0", *minutes, *seconds),
+        [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        _ => return None,
+    };
+    let hours: u64 = hours.parse().ok()?;
+    let minutes: u64 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    if minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    Some(Duration::from_secs(hours * 3600 + minutes * 60) + Duration::from_secs_f64(seconds))
+}
+
+/// Packets per second the launcher at location `source` sends while ready.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PacketSource {
+    pub source: String,
+    pub rate: u64,
+}
+
+/// The waveform of a signal. Times are in ticks, strengths in dBm.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SourceConfig {
+    Sine {
+        period: f64,
+        amplitude: f64,
+        /// Uniform noise, as a fraction of the amplitude.
+        #[serde(default)]
+        noise: f64,
+    },
+    Square {
+        period: f64,
+        amplitude: f64,
+    },
+    Sawtooth {
+        period: f64,
+        amplitude: f64,
+    },
+    RandomWalk {
+        /// Largest change from one sample to the next.
+        step: f64,
+        amplitude: f64,
+    },
+    /// Perlin-style gradient noise, changing course about every `period`.
+    Noise {
+        period: f64,
+        amplitude: f64,
+    },
+    /// A sine wave whose period sweeps from `from` to `to` over `sweep`.
+    Chirp {
+        from: f64,
+        to: f64,
+        sweep: f64,
+        amplitude: f64,
+    },
+    Sum {
+        sources: Vec<SourceConfig>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalMarker {
+    Dot,
+    Block,
+    #[default]
+    Braille,
+}
+
+/// A line of the Signals chart.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignalConfig {
+    pub name: String,
+    pub source: SourceConfig,
+    #[serde(default)]
+    pub marker: SignalMarker,
+    /// A color as in theme files. Left out, the signals take the theme's
+    /// `wave1` and `wave2` in turn.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Points per tick.
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+}
+
+/// How the Signals chart is scaled.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChartConfig {
+    /// Fit the strength axis to the points instead of `y_bounds`.
+    #[serde(default)]
+    pub auto_scale: bool,
+    #[serde(default = "default_y_bounds")]
+    pub y_bounds: [f64; 2],
+}
+
+impl Default for ChartConfig {
+    fn default() -> ChartConfig {
+        ChartConfig {
+            auto_scale: false,
+            y_bounds: default_y_bounds(),
+        }
+    }
+}
+
+fn default_y_bounds() -> [f64; 2] {
+    [-20.0, 20.0]
+}
+
+/// Most points per tick a signal may draw.
+const MAX_SAMPLES: usize = 100;
+
+fn default_samples() -> usize {
+    5
+}
+
+/// Everything a training session is built from: the launcher sites, the
+/// messages rotating through the system log, the packet rates of the sites,
+/// the pre-launch checklist, the signals on the chart and the events
+/// scripted on the timeline.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub launchers: Vec<Launcher>,
+    pub logs: Vec<LogMessage>,
+    /// Sites left out send `traffic::DEFAULT_RATE` packets per second.
+    #[serde(default)]
+    pub packets: Vec<PacketSource>,
+    /// The built-in checklist when left out.
+    #[serde(default = "default_checklist")]
+    pub checklist: Vec<ChecklistItem>,
+    /// The two built-in sine waves when left out.
+    #[serde(default = "default_signals")]
+    pub signals: Vec<SignalConfig>,
+    #[serde(default)]
+    pub chart: ChartConfig,
+    #[serde(default)]
+    pub timeline: Vec<TimelineEvent>,
+}
+
+fn default_checklist() -> Vec<ChecklistItem> {
+    CHECKLIST
+        .iter()
+        .map(|&(label, mandatory)| ChecklistItem {
+            label: label.to_string(),
+            mandatory,
+            checked: false,
+        })
+        .collect()
+}
+
+fn default_signals() -> Vec<SignalConfig> {
+    vec![
+        SignalConfig {
+            name: "CS Wave".to_string(),
+            source: SourceConfig::Sine {
+                period: 3.0 * TAU,
+                amplitude: 16.0,
+                noise: 0.1,
+            },
+            marker: SignalMarker::Dot,
+            color: None,
+            samples: 5,
+        },
+        SignalConfig {
+            name: "IE Wave".to_string(),
+            source: SourceConfig::Sine {
+                period: 2.0 * TAU,
+                amplitude: 8.0,
+                noise: 0.1,
+            },
+            marker: SignalMarker::Braille,
+            color: None,
+            samples: 10,
+        },
+    ]
+}
+
+/// Collect the problems of `source`, found at `path` in the scenario.
+fn check_source(path: &str, source: &SourceConfig, problems: &mut Vec<String>) {
+    let mut positive = |field: &str, value: f64| {
+        if !(value.is_finite() && value > 0.0) {
+            problems.push(format!("{}.{}: {} is not positive", path, field, value));
+        }
+    };
+    match *source {
+        SourceConfig::Sine {
+            period,
+            amplitude,
+            noise,
+        } => {
+            positive("period", period);
+            positive("amplitude", amplitude);
+            if !(0.0..=1.0).contains(&noise) {
+                problems.push(format!("{}.noise: {} is outside [0, 1]", path, noise));
+            }
+        }
+        SourceConfig::Square { period, amplitude }
+        | SourceConfig::Sawtooth { period, amplitude }
+        | SourceConfig::Noise { period, amplitude } => {
+            positive("period", period);
+            positive("amplitude", amplitude);
+        }
+        SourceConfig::RandomWalk { step, amplitude } => {
+            positive("step", step);
+            positive("amplitude", amplitude);
+        }
+        SourceConfig::Chirp {
+            from,
+            to,
+            sweep,
+            amplitude,
+        } => {
+            positive("from", from);
+            positive("to", to);
+            positive("sweep", sweep);
+            positive("amplitude", amplitude);
+        }
+        SourceConfig::Sum { ref sources } => {
+            if sources.is_empty() {
+                problems.push(format!("{}.sources: at least one source is required", path));
+            }
+            for (i, source) in sources.iter().enumerate() {
+                check_source(&format!("{}.sources[{}]", path, i), source, problems);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    UnsupportedFormat(String),
+    Parse(String),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "cannot read scenario: {}", err),
+            ScenarioError::UnsupportedFormat(ext) => write!(
+                f,
+                "unsupported scenario format {:?}, expected .toml or .json",
+                ext
+            ),
+            ScenarioError::Parse(msg) => write!(f, "cannot parse scenario: {}", msg),
+            ScenarioError::Invalid(problems) => {
+                write!(f, "invalid scenario:")?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ScenarioError {}
+
+impl Default for Scenario {
+    fn default() -> Scenario {
+        Scenario {
+            launchers: LAUNCHERS
+                .iter()
+                .map(|&(name, location, coords, state)| Launcher {
+                    name: name.to_string(),
+                    location: location.to_string(),
+                    coords,
+                    state,
+                    ticks: 0,
+                })
+                .collect(),
+            logs: LOGS
+                .iter()
+                .map(|&(message, level)| LogMessage {
+                    message: message.to_string(),
+                    level,
+                })
+                .collect(),
+            packets: PACKETS
+                .iter()
+                .map(|&(source, rate)| PacketSource {
+                    source: source.to_string(),
+                    rate,
+                })
+                .collect(),
+            checklist: default_checklist(),
+            signals: default_signals(),
+            chart: ChartConfig::default(),
+            timeline: Vec::new(),
+        }
+    }
+}
+
+impl Scenario {
+    /// Load a scenario from a `.toml` or `.json` file and validate it.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Scenario, ScenarioError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let scenario: Scenario = match ext.as_str() {
+            "toml" => {
+                toml::from_str(&content).map_err(|err| ScenarioError::Parse(err.to_string()))?
+            }
+            "json" => serde_json::from_str(&content)
+                .map_err(|err| ScenarioError::Parse(err.to_string()))?,
+            _ => return Err(ScenarioError::UnsupportedFormat(ext)),
+        };
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Check the scenario for problems the dashboard cannot cope with,
+    /// reporting all of them at once.
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        let mut problems = Vec::new();
+
+        if self.launchers.is_empty() {
+            problems.push("at least one launcher is required".to_string());
+        }
+        let mut names = HashSet::new();
+        for (i, launcher) in self.launchers.iter().enumerate() {
+            let (lat, lon) = launcher.coords;
+            if launcher.name.is_empty() {
+                problems.push(format!("launchers[{}]: name is empty", i));
+            } else if !names.insert(launcher.name.as_str()) {
+                problems.push(format!(
+                    "launchers[{}]: duplicate name {:?}",
+                    i, launcher.name
+                ));
+            }
+            if launcher.location.is_empty() {
+                problems.push(format!("launchers[{}]: location is empty", i));
+            }
+            if !(-90.0..=90.0).contains(&lat) {
+                problems.push(format!(
+                    "launchers[{}]: latitude {} is outside [-90, 90]",
+                    i, lat
+                ));
+            }
+            if !(-180.0..=180.0).contains(&lon) {
+                problems.push(format!(
+                    "launchers[{}]: longitude {} is outside [-180, 180]",
+                    i, lon
+                ));
+            }
+        }
+
+        if self.logs.is_empty() {
+            problems.push("at least one log message is required".to_string());
+        }
+
+        let mut sources = HashSet::new();
+        for (i, packet) in self.packets.iter().enumerate() {
+            if packet.source.is_empty() {
+                problems.push(format!("packets[{}]: source is empty", i));
+            } else if !sources.insert(packet.source.as_str()) {
+                problems.push(format!(
+                    "packets[{}]: duplicate source {:?}",
+                    i, packet.source
+                ));
+            } else if !self
+                .launchers
+                .iter()
+                .any(|launcher| launcher.location == packet.source)
+            {
+                problems.push(format!(
+                    "packets[{}]: no launcher at {:?}",
+                    i, packet.source
+                ));
+            }
+        }
+
+        let mut labels = HashSet::new();
+        for (i, item) in self.checklist.iter().enumerate() {
+            if item.label.is_empty() {
+                problems.push(format!("checklist[{}]: label is empty", i));
+            } else if !labels.insert(item.label.as_str()) {
+                problems.push(format!(
+                    "checklist[{}]: duplicate label {:?}",
+                    i, item.label
+                ));
+            }
+        }
+
+        let mut names = HashSet::new();
+        for (i, signal) in self.signals.iter().enumerate() {
+            if signal.name.is_empty() {
+                problems.push(format!("signals[{}]: name is empty", i));
+            } else if !names.insert(signal.name.as_str()) {
+                problems.push(format!("signals[{}]: duplicate name {:?}", i, signal.name));
+            }
+            if let Some(color) = &signal.color {
+                if parse_color(color).is_none() {
+                    problems.push(format!("signals[{}]: unknown color {:?}", i, color));
+                }
+            }
+            if !(1..=MAX_SAMPLES).contains(&signal.samples) {
+                problems.push(format!(
+                    "signals[{}]: samples {} is outside [1, {}]",
+                    i, signal.samples, MAX_SAMPLES
+                ));
+            }
+            check_source(
+                &format!("signals[{}].source", i),
+                &signal.source,
+                &mut problems,
+            );
+        }
+
+        let [low, high] = self.chart.y_bounds;
+        if !(low.is_finite() && high.is_finite() && low < high) {
+            problems.push(format!(
+                "chart.y_bounds: [{}, {}] is not an increasing range",
+                low, high
+            ));
+        }
+
+        for (i, event) in self.timeline.iter().enumerate() {
+            if parse_time(&event.at).is_none() {
+                problems.push(format!(
+                    "timeline[{}]: at {:?} is not mm:ss or hh:mm:ss",
+                    i, event.at
+                ));
+            }
+            match (&event.launcher, event.state) {
+                (Some(launcher), Some(_)) => {
+                    if !self
+                        .launchers
+                        .iter()
+                        .any(|l| l.name == *launcher || l.location == *launcher)
+                    {
+                        problems.push(format!(
+                            "timeline[{}]: no launcher named or at {:?}",
+                            i, launcher
+                        ));
+                    }
+                }
+                (None, None) => {}
+                _ => problems.push(format!("timeline[{}]: launcher and state go together", i)),
+            }
+            if event.log.as_ref().is_some_and(|log| log.message.is_empty()) {
+                problems.push(format!("timeline[{}]: log message is empty", i));
+            }
+            if let Some(defcon) = event.defcon {
+                if !(1..=DEFCON_PEACE).contains(&defcon) {
+                    problems.push(format!(
+                        "timeline[{}]: defcon {} is outside [1, {}]",
+                        i, defcon, DEFCON_PEACE
+                    ));
+                }
+            }
+            if event.state.is_none() && event.log.is_none() && event.defcon.is_none() {
+                problems.push(format!("timeline[{}]: does nothing", i));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ScenarioError::Invalid(problems))
+        }
+    }
+}
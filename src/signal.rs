@@ -0,0 +1,313 @@
+//! Waveforms behind the Signals chart.
+//!
+//! A `SignalSource` gives the strength of a signal at a point in time, and a
+//! `Sampler` steps through time to turn it into points for the chart. Sources
+//! are built from the scenario's `SourceConfig`s.
+
+use crate::scenario::SourceConfig;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f64::consts::TAU;
+
+/// Lattice points of `Noise` before its gradients repeat.
+const NOISE_GRADIENTS: usize = 256;
+
+pub trait SignalSource {
+    /// Strength at time `x`. Called with increasing `x`, so sources may keep
+    /// state between calls.
+    fn value(&mut self, x: f64) -> f64;
+}
+
+/// How far into its cycle of length `period` time `x` is, in `[0, 1)`.
+fn phase(x: f64, period: f64) -> f64 {
+    (x / period).rem_euclid(1.0)
+}
+
+/// A sine wave with uniform noise of up to `noise` times its amplitude.
+pub struct Sine {
+    period: f64,
+    amplitude: f64,
+    noise: f64,
+    rng: StdRng,
+}
+
+impl SignalSource for Sine {
+    fn value(&mut self, x: f64) -> f64 {
+        let noise = if self.noise > 0.0 {
+            self.rng.gen_range(-self.noise..self.noise)
+        } else {
+            0.0
+        };
+        ((TAU * x / self.period).sin() + noise) * self.amplitude
+    }
+}
+
+pub struct Square {
+    period: f64,
+    amplitude: f64,
+}
+
+impl SignalSource for Square {
+    fn value(&mut self, x: f64) -> f64 {
+        if phase(x, self.period) < 0.5 {
+            self.amplitude
+        } else {
+            -self.amplitude
+        }
+    }
+}
+
+/// Rises from `-amplitude` to `amplitude` over each period, then drops back.
+pub struct Sawtooth {
+    period: f64,
+    amplitude: f64,
+}
+
+impl SignalSource for Sawtooth {
+    fn value(&mut self, x: f64) -> f64 {
+        (2.0 * phase(x, self.period) - 1.0) * self.amplitude
+    }
+}
+
+/// Moves up or down by up to `step` every sample, bouncing off
+/// `±amplitude`.
+pub struct RandomWalk {
+    step: f64,
+    amplitude: f64,
+    y: f64,
+    rng: StdRng,
+}
+
+impl SignalSource for RandomWalk {
+    fn value(&mut self, _x: f64) -> f64 {
+        if self.step > 0.0 {
+            self.y += self.rng.gen_range(-self.step..self.step);
+        }
+        if self.y > self.amplitude {
+            self.y = 2.0 * self.amplitude - self.y;
+        } else if self.y < -self.amplitude {
+            self.y = -2.0 * self.amplitude - self.y;
+        }
+        self.y = self.y.clamp(-self.amplitude, self.amplitude);
+        self.y
+    }
+}
+
+/// One-dimensional gradient noise in the manner of Perlin's: a random slope
+/// at every `period`, smoothly blended in between.
+pub struct Noise {
+    period: f64,
+    amplitude: f64,
+    gradients: Vec<f64>,
+}
+
+impl Noise {
+    fn gradient(&self, i: f64) -> f64 {
+        self.gradients[(i as i64).rem_euclid(NOISE_GRADIENTS as i64) as usize]
+    }
+}
+
+impl SignalSource for Noise {
+    fn value(&mut self, x: f64) -> f64 {
+        let t = x / self.period;
+        let i = t.floor();
+        let f = t - i;
+        let fade = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+        let left = self.gradient(i) * f;
+        let right = self.gradient(i + 1.0) * (f - 1.0);
+        // Gradient noise stays within ±0.5.
+        2.0 * (left + fade * (right - left)) * self.amplitude
+    }
+}
+
+/// A sine wave whose period sweeps from `from` to `to` over `sweep`, then
+/// starts over.
+pub struct Chirp {
+    from: f64,
+    to: f64,
+    sweep: f64,
+    amplitude: f64,
+}
+
+impl SignalSource for Chirp {
+    fn value(&mut self, x: f64) -> f64 {
+        let t = x.rem_euclid(self.sweep);
+        let (f0, f1) = (1.0 / self.from, 1.0 / self.to);
+        let cycles = f0 * t + (f1 - f0) * t * t / (2.0 * self.sweep);
+        (TAU * cycles).sin() * self.amplitude
+    }
+}
+
+/// Several sources added up.
+pub struct Sum {
+    sources: Vec<Box<dyn SignalSource>>,
+}
+
+impl SignalSource for Sum {
+    fn value(&mut self, x: f64) -> f64 {
+        self.sources.iter_mut().map(|source| source.value(x)).sum()
+    }
+}
+
+/// Build the source `config` describes. Random sources draw from `rng`, the
+/// parts of a sum from generators seeded by it.
+pub fn build(config: &SourceConfig, mut rng: StdRng) -> Box<dyn SignalSource> {
+    match *config {
+        SourceConfig::Sine {
+            period,
+            amplitude,
+            noise,
+        } => Box::new(Sine {
+            period,
+            amplitude,
+            noise,
+            rng,
+        }),
+        SourceConfig::Square { period, amplitude } => Box::new(Square { period, amplitude }),
+        SourceConfig::Sawtooth { period, amplitude } => Box::new(Sawtooth { period, amplitude }),
+        SourceConfig::RandomWalk { step, amplitude } => Box::new(RandomWalk {
+            step,
+            amplitude,
+            y: 0.0,
+            rng,
+        }),
+        SourceConfig::Noise { period, amplitude } => Box::new(Noise {
+            period,
+            amplitude,
+            gradients: (0..NOISE_GRADIENTS)
+                .map(|_| rng.gen_range(-1.0..=1.0))
+                .collect(),
+        }),
+        SourceConfig::Chirp {
+            from,
+            to,
+            sweep,
+            amplitude,
+        } => Box::new(Chirp {
+            from,
+            to,
+            sweep,
+            amplitude,
+        }),
+        SourceConfig::Sum { ref sources } => Box::new(Sum {
+            sources: sources
+                .iter()
+                .map(|source| build(source, StdRng::seed_from_u64(rng.gen())))
+                .collect(),
+        }),
+    }
+}
+
+/// Samples a source every `interval`, starting at 0.
+pub struct Sampler {
+    source: Box<dyn SignalSource>,
+    x: f64,
+    interval: f64,
+}
+
+impl Sampler {
+    pub fn new(source: Box<dyn SignalSource>, interval: f64) -> Sampler {
+        Sampler {
+            source,
+            x: 0.0,
+            interval,
+        }
+    }
+}
+
+impl Iterator for Sampler {
+    type Item = (f64, f64);
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = (self.x, self.source.value(self.x));
+        self.x += self.interval;
+        Some(point)
+    }
+}
+
+/// The last `capacity` values pushed, readable as one slice.
+///
+/// Every value is stored twice, `capacity` apart, so the window of values
+/// never wraps around the end of the buffer and pushing costs the same
+/// however long it is.
+pub struct RingBuffer<T> {
+    buf: Vec<T>,
+    capacity: usize,
+    start: usize,
+    len: usize,
+}
+
+impl<T: Copy + Default> RingBuffer<T> {
+    pub fn with_capacity(capacity: usize) -> RingBuffer<T> {
+        RingBuffer {
+            buf: vec![T::default(); 2 * capacity],
+            capacity,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Add `value` at the end, dropping the oldest value when full.
+    pub fn push(&mut self, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        let end = (self.start + self.len) % self.capacity;
+        self.buf[end] = value;
+        self.buf[end + self.capacity] = value;
+        if self.len < self.capacity {
+            self.len += 1;
+        } else {
+            self.start = (self.start + 1) % self.capacity;
+        }
+    }
+
+    /// The values, oldest first.
+    pub fn as_slice(&self) -> &[T] {
+        &self.buf[self.start..self.start + self.len]
+    }
+}
+
+impl<T: Copy + Default> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_fills_up_in_order() {
+        let mut ring = RingBuffer::with_capacity(4);
+        assert!(ring.as_slice().is_empty());
+        ring.extend([1, 2, 3]);
+        assert_eq!(ring.as_slice(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn ring_buffer_keeps_the_newest_values_across_wraparounds() {
+        let mut ring = RingBuffer::with_capacity(4);
+        for value in 1..=11u32 {
+            ring.push(value);
+            let oldest = value.saturating_sub(3).max(1);
+            let expected: Vec<_> = (oldest..=value).collect();
+            assert_eq!(ring.as_slice(), expected, "after pushing {}", value);
+        }
+    }
+
+    #[test]
+    fn ring_buffer_of_one_holds_the_last_value() {
+        let mut ring = RingBuffer::with_capacity(1);
+        ring.extend([1, 2, 3]);
+        assert_eq!(ring.as_slice(), [3]);
+    }
+
+    #[test]
+    fn ring_buffer_without_capacity_stays_empty() {
+        let mut ring = RingBuffer::with_capacity(0);
+        ring.extend([1, 2, 3]);
+        assert!(ring.as_slice().is_empty());
+    }
+}
@@ -0,0 +1,360 @@
+//! Color palettes for the dashboard, see `Theme::resolve`.
+
+use serde::Deserialize;
+use std::{error::Error, fmt, fs, io, path::Path};
+use tui::style::Color;
+
+pub const PRESETS: [&str; 5] = [
+    "default",
+    "amber-crt",
+    "green-phosphor",
+    "high-contrast",
+    "monochrome",
+];
+
+/// Defines `Theme` with one color per role, and `Colors`, the same roles as
+/// optional color names for theme files.
+macro_rules! roles {
+    ($($(#[$doc:meta])* $role:ident,)*) => {
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Theme {
+            $($(#[$doc])* pub $role: Color,)*
+        }
+
+        #[derive(Debug, Default, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Colors {
+            $($role: Option<String>,)*
+        }
+
+        impl Colors {
+            /// Override the colors of `theme`, collecting unknown color names.
+            fn apply(self, theme: &mut Theme, problems: &mut Vec<String>) {
+                $(if let Some(name) = self.$role {
+                    match parse_color(&name) {
+                        Some(color) => theme.$role = color,
+                        None => problems.push(format!(
+                            "colors.{}: unknown color {:?}",
+                            stringify!($role),
+                            name
+                        )),
+                    }
+                })*
+            }
+        }
+    };
+}
+
+roles! {
+    /// Tab titles.
+    tab,
+    /// The selected tab title.
+    tab_highlight,
+    /// Header border and banner while the launch panel is locked.
+    alarm,
+    alarm_text,
+    popup_border,
+    popup_background,
+    popup_text,
+    /// Key hints in popups.
+    help,
+    success,
+    failure,
+    offline,
+    booting,
+    ready,
+    armed,
+    launching,
+    reloading,
+    fault,
+    gauge,
+    gauge_background,
+    sparkline,
+    chart_title,
+    axis,
+    wave1,
+    wave2,
+    info,
+    warning,
+    error,
+    critical,
+    /// Text matching the System Message search.
+    search,
+    bar,
+    /// Packet counts, printed on top of the bars.
+    bar_value,
+    bar_label,
+    table_header,
+    map,
+    /// Planned part of the missile's arc.
+    trajectory,
+    /// Flown part of the missile's arc.
+    trail,
+    target,
+    missile,
+    satellite,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            tab: Color::Green,
+            tab_highlight: Color::Yellow,
+            alarm: Color::Red,
+            alarm_text: Color::White,
+            popup_border: Color::Magenta,
+            popup_background: Color::Black,
+            popup_text: Color::Yellow,
+            help: Color::White,
+            success: Color::Green,
+            failure: Color::Red,
+            offline: Color::DarkGray,
+            booting: Color::Yellow,
+            ready: Color::Green,
+            armed: Color::Magenta,
+            launching: Color::LightRed,
+            reloading: Color::Cyan,
+            fault: Color::Red,
+            gauge: Color::Magenta,
+            gauge_background: Color::Black,
+            sparkline: Color::Green,
+            chart_title: Color::Cyan,
+            axis: Color::Gray,
+            wave1: Color::Cyan,
+            wave2: Color::Yellow,
+            info: Color::Blue,
+            warning: Color::Yellow,
+            error: Color::Magenta,
+            critical: Color::Red,
+            search: Color::LightYellow,
+            bar: Color::Cyan,
+            bar_value: Color::Black,
+            bar_label: Color::Yellow,
+            table_header: Color::Yellow,
+            map: Color::White,
+            trajectory: Color::DarkGray,
+            trail: Color::Red,
+            target: Color::Yellow,
+            missile: Color::Red,
+            satellite: Color::Cyan,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    Parse(String),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(err) => write!(f, "cannot read theme: {}", err),
+            ThemeError::Parse(msg) => write!(f, "cannot parse theme: {}", msg),
+            ThemeError::Invalid(problems) => {
+                write!(f, "invalid theme:")?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for ThemeError {}
+
+/// A theme file: a preset to start from and the colors to change.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    base: Option<String>,
+    #[serde(default)]
+    colors: Colors,
+}
+
+impl Theme {
+    /// One of the built-in `PRESETS`.
+    pub fn preset(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "default" => Theme::default(),
+            "amber-crt" => Theme::phosphor(
+                Color::Rgb(255, 176, 0),
+                Color::Rgb(153, 102, 0),
+                Color::Rgb(255, 220, 140),
+            ),
+            "green-phosphor" => Theme::phosphor(
+                Color::Rgb(51, 255, 51),
+                Color::Rgb(0, 143, 17),
+                Color::Rgb(190, 255, 190),
+            ),
+            "high-contrast" => Theme::high_contrast(),
+            "monochrome" => Theme::monochrome(),
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// A preset name, or else the path of a theme file.
+    pub fn resolve(name: &str) -> Result<Theme, ThemeError> {
+        match Theme::preset(name) {
+            Some(theme) => Ok(theme),
+            None => Theme::load(name),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme, ThemeError> {
+        let content = fs::read_to_string(path).map_err(ThemeError::Io)?;
+        let file: ThemeFile =
+            toml::from_str(&content).map_err(|err| ThemeError::Parse(err.to_string()))?;
+        let base = file.base.as_deref().unwrap_or("default");
+        let mut problems = Vec::new();
+        let mut theme = Theme::preset(base).unwrap_or_else(|| {
+            problems.push(format!(
+                "base: unknown preset {:?}, expected one of {}",
+                base,
+                PRESETS.join(", ")
+            ));
+            Theme::default()
+        });
+        file.colors.apply(&mut theme, &mut problems);
+        if problems.is_empty() {
+            Ok(theme)
+        } else {
+            Err(ThemeError::Invalid(problems))
+        }
+    }
+
+    /// Every role in one color, with a dim and a bright shade for inactive
+    /// and alarming things, like an old single-color CRT.
+    fn phosphor(color: Color, dim: Color, bright: Color) -> Theme {
+        Theme {
+            tab: dim,
+            tab_highlight: bright,
+            alarm: bright,
+            alarm_text: Color::Black,
+            popup_border: bright,
+            popup_background: Color::Black,
+            popup_text: color,
+            help: dim,
+            success: color,
+            failure: bright,
+            offline: dim,
+            booting: dim,
+            ready: color,
+            armed: bright,
+            launching: bright,
+            reloading: dim,
+            fault: bright,
+            gauge: color,
+            gauge_background: Color::Black,
+            sparkline: color,
+            chart_title: bright,
+            axis: dim,
+            wave1: color,
+            wave2: bright,
+            info: dim,
+            warning: color,
+            error: bright,
+            critical: bright,
+            search: bright,
+            bar: color,
+            bar_value: Color::Black,
+            bar_label: color,
+            table_header: bright,
+            map: dim,
+            trajectory: dim,
+            trail: bright,
+            target: bright,
+            missile: bright,
+            satellite: color,
+        }
+    }
+
+    fn high_contrast() -> Theme {
+        Theme {
+            tab: Color::White,
+            tab_highlight: Color::LightYellow,
+            alarm: Color::LightRed,
+            alarm_text: Color::Black,
+            popup_border: Color::White,
+            popup_text: Color::White,
+            success: Color::LightGreen,
+            failure: Color::LightRed,
+            offline: Color::Gray,
+            booting: Color::LightYellow,
+            ready: Color::LightGreen,
+            armed: Color::LightMagenta,
+            launching: Color::LightRed,
+            reloading: Color::LightCyan,
+            fault: Color::LightRed,
+            gauge: Color::White,
+            sparkline: Color::LightGreen,
+            chart_title: Color::White,
+            axis: Color::White,
+            wave1: Color::LightCyan,
+            wave2: Color::LightYellow,
+            info: Color::White,
+            warning: Color::LightYellow,
+            error: Color::LightMagenta,
+            critical: Color::LightRed,
+            search: Color::LightYellow,
+            bar: Color::White,
+            bar_label: Color::White,
+            table_header: Color::LightYellow,
+            trajectory: Color::Gray,
+            trail: Color::LightRed,
+            target: Color::LightYellow,
+            missile: Color::LightRed,
+            satellite: Color::LightCyan,
+            ..Theme::default()
+        }
+    }
+
+    /// The terminal's own colors everywhere, used for `NO_COLOR`.
+    pub fn monochrome() -> Theme {
+        let mut theme = Theme::phosphor(Color::Reset, Color::Reset, Color::Reset);
+        theme.alarm_text = Color::Reset;
+        theme.popup_background = Color::Reset;
+        theme.gauge_background = Color::Reset;
+        theme.bar_value = Color::Reset;
+        theme
+    }
+}
+
+/// A color name such as `light_red`, `#ffb000`, or an index into the
+/// 256-color palette.
+pub fn parse_color(name: &str) -> Option<Color> {
+    if let Some(hex) = name.strip_prefix('#') {
+        return match hex::decode(hex).ok()?.as_slice() {
+            &[r, g, b] => Some(Color::Rgb(r, g, b)),
+            _ => None,
+        };
+    }
+    if let Ok(index) = name.parse() {
+        return Some(Color::Indexed(index));
+    }
+    let color = match name.to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark_gray" | "dark_grey" | "darkgray" => Color::DarkGray,
+        "light_red" => Color::LightRed,
+        "light_green" => Color::LightGreen,
+        "light_yellow" => Color::LightYellow,
+        "light_blue" => Color::LightBlue,
+        "light_magenta" => Color::LightMagenta,
+        "light_cyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(color)
+}
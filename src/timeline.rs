@@ -0,0 +1,111 @@
+//! Scheduler for the events scripted in a scenario's timeline.
+//!
+//! Events fire on the first tick at or after their time, events at the same
+//! time in the order of the scenario file.
+
+use crate::scenario::{parse_time, TimelineEvent};
+use std::{collections::VecDeque, time::Duration};
+
+pub struct Timeline {
+    /// Events still to fire, earliest first.
+    pending: VecDeque<(Duration, TimelineEvent)>,
+}
+
+impl Timeline {
+    /// Events whose time cannot be parsed are left out, `Scenario::validate`
+    /// reports them.
+    pub fn new(events: &[TimelineEvent]) -> Timeline {
+        let mut pending: Vec<_> = events
+            .iter()
+            .filter_map(|event| Some((parse_time(&event.at)?, event.clone())))
+            .collect();
+        pending.sort_by_key(|&(at, _)| at);
+        Timeline {
+            pending: pending.into(),
+        }
+    }
+
+    /// Take the events due by `time`.
+    pub fn due(&mut self, time: Duration) -> Vec<TimelineEvent> {
+        let mut due = Vec::new();
+        while self.pending.front().is_some_and(|&(at, _)| at <= time) {
+            due.extend(self.pending.pop_front().map(|(_, event)| event));
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{logs::LogLevel, scenario::LogMessage};
+
+    /// An event at `at` that only logs `message`.
+    fn event(at: &str, message: &str) -> TimelineEvent {
+        TimelineEvent {
+            at: at.to_string(),
+            launcher: None,
+            state: None,
+            log: Some(LogMessage {
+                message: message.to_string(),
+                level: LogLevel::Info,
+            }),
+            defcon: None,
+        }
+    }
+
+    fn messages(events: Vec<TimelineEvent>) -> Vec<String> {
+        events
+            .into_iter()
+            .map(|event| event.log.unwrap().message)
+            .collect()
+    }
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    #[test]
+    fn events_come_due_earliest_first() {
+        let mut timeline = Timeline::new(&[
+            event("01:00", "third"),
+            event("00:05", "first"),
+            event("00:30", "second"),
+        ]);
+        assert!(timeline.due(secs(4.9)).is_empty());
+        assert_eq!(messages(timeline.due(secs(5.0))), ["first"]);
+        assert_eq!(messages(timeline.due(secs(120.0))), ["second", "third"]);
+    }
+
+    #[test]
+    fn events_fire_once() {
+        let mut timeline = Timeline::new(&[event("00:01", "once")]);
+        assert_eq!(messages(timeline.due(secs(1.0))), ["once"]);
+        assert!(timeline.due(secs(1.0)).is_empty());
+        assert!(timeline.due(secs(60.0)).is_empty());
+    }
+
+    #[test]
+    fn events_at_the_same_time_keep_the_file_order() {
+        let mut timeline = Timeline::new(&[
+            event("00:02", "b"),
+            event("00:01", "a"),
+            event("00:02", "c"),
+            event("00:02.0", "d"),
+        ]);
+        assert_eq!(messages(timeline.due(secs(2.0))), ["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn events_between_ticks_fire_on_the_next_one() {
+        let mut timeline = Timeline::new(&[event("00:00.25", "late"), event("00:00.21", "early")]);
+        assert!(timeline.due(secs(0.2)).is_empty());
+        assert_eq!(messages(timeline.due(secs(0.3))), ["early", "late"]);
+    }
+
+    #[test]
+    fn unparsable_times_are_left_out() {
+        let mut timeline = Timeline::new(&[event("soon", "never"), event("00:61", "never")]);
+        assert!(timeline.due(Duration::MAX).is_empty());
+    }
+}
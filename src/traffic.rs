@@ -0,0 +1,125 @@
+//! Packet traffic of the launcher sites, shown in the Packets chart.
+//!
+//! Every launcher sends packets at its site's rate, scaled by what it is
+//! doing: nothing while offline, the most while launching. An alert about a
+//! site makes its traffic burst for a while. The chart shows how many
+//! packets each site sent over the last `WINDOW` ticks, and a site sending
+//! `SPIKE_FACTOR` times what it would sitting ready is reported as a spike.
+
+use crate::{
+    app::{Launcher, LauncherState},
+    scenario::PacketSource,
+};
+use rand::{rngs::StdRng, Rng};
+use std::{collections::VecDeque, time::Duration};
+
+/// Ticks the packet counts are summed over.
+pub const WINDOW: usize = 50;
+/// Packets per second of a site without a rate in the scenario.
+pub const DEFAULT_RATE: f64 = 5.0;
+/// Ticks an alert keeps a site's traffic up.
+const BURST_TICKS: u64 = 30;
+const BURST_FACTOR: f64 = 3.0;
+/// Times the ready volume over `WINDOW` a site has to send to spike.
+const SPIKE_FACTOR: f64 = 2.5;
+
+/// How busy a launcher keeps its link compared to sitting ready.
+fn load(state: LauncherState) -> f64 {
+    match state {
+        LauncherState::Offline => 0.0,
+        LauncherState::Booting => 0.5,
+        LauncherState::Ready => 1.0,
+        LauncherState::Armed => 1.5,
+        LauncherState::Launching => 4.0,
+        LauncherState::Reloading => 0.5,
+        LauncherState::Fault => 0.2,
+    }
+}
+
+struct Link {
+    /// Packets per second while ready.
+    rate: f64,
+    /// Packets sent on each of the last `WINDOW` ticks, newest last.
+    sent: VecDeque<u64>,
+    /// Sum of `sent`.
+    total: u64,
+    /// Ticks left of the current burst.
+    burst: u64,
+    /// Over `SPIKE_FACTOR` times the ready volume, until back to it.
+    spiking: bool,
+}
+
+/// One link per launcher, in the order of `App::launchers`.
+pub struct Traffic {
+    links: Vec<Link>,
+    rng: StdRng,
+}
+
+impl Traffic {
+    /// Rates come from the scenario's packet sources, matched to the
+    /// launchers by location.
+    pub fn new(launchers: &[Launcher], packets: &[PacketSource], rng: StdRng) -> Traffic {
+        let links = launchers
+            .iter()
+            .map(|launcher| Link {
+                rate: packets
+                    .iter()
+                    .find(|packet| packet.source == launcher.location)
+                    .map_or(DEFAULT_RATE, |packet| packet.rate as f64),
+                sent: VecDeque::with_capacity(WINDOW),
+                total: 0,
+                burst: 0,
+                spiking: false,
+            })
+            .collect();
+        Traffic { links, rng }
+    }
+
+    /// Make the traffic of launcher `i` burst.
+    pub fn alert(&mut self, i: usize) {
+        if let Some(link) = self.links.get_mut(i) {
+            link.burst = BURST_TICKS;
+        }
+    }
+
+    pub fn alert_all(&mut self) {
+        for link in &mut self.links {
+            link.burst = BURST_TICKS;
+        }
+    }
+
+    /// Send one tick's worth of packets from every launcher. Returns the
+    /// launchers whose traffic started to spike.
+    pub fn on_tick(&mut self, launchers: &[Launcher], tick_rate: Duration) -> Vec<usize> {
+        let mut spikes = Vec::new();
+        for (i, (link, launcher)) in self.links.iter_mut().zip(launchers).enumerate() {
+            let mut expected = link.rate * load(launcher.state) * tick_rate.as_secs_f64();
+            if link.burst > 0 {
+                link.burst -= 1;
+                expected *= BURST_FACTOR;
+            }
+            // Jitter the volume and round at random, so that low rates
+            // still send the odd packet.
+            let expected = expected * self.rng.gen_range(0.5..1.5);
+            let sent = (expected + self.rng.gen::<f64>()).floor() as u64;
+            if link.sent.len() == WINDOW {
+                link.total -= link.sent.pop_front().unwrap_or(0);
+            }
+            link.sent.push_back(sent);
+            link.total += sent;
+            let ready = link.rate * (tick_rate * WINDOW as u32).as_secs_f64();
+            if !link.spiking && link.total as f64 > SPIKE_FACTOR * ready {
+                link.spiking = true;
+                spikes.push(i);
+            } else if link.spiking && link.total as f64 <= ready {
+                link.spiking = false;
+            }
+        }
+        spikes
+    }
+
+    /// Packets each launcher sent over the last `WINDOW` ticks.
+    pub fn totals(&self) -> Vec<u64> {
+        self.links.iter().map(|link| link.total).collect()
+    }
+}
@@ -1,4 +1,15 @@
-use crate::app::App;
+use crate::{
+    app::{
+        App, Areas, LaunchSequence, LauncherState, Target, LAUNCH_STEPS, MISSILE_SPEED, TICK_RATE,
+    },
+    geo,
+    keys::{Action, Keymap},
+    logs::LogLevel,
+    scenario::SignalMarker,
+    theme::Theme,
+    traffic::WINDOW,
+};
+use std::collections::HashSet;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -6,8 +17,8 @@ use tui::{
     symbols,
     text::{Span, Spans},
     widgets::{
-        canvas::{Canvas, Line, Map, MapResolution, Rectangle},
-        Clear, Paragraph,
+        canvas::{Canvas, Line, Map, MapResolution, Points, Rectangle},
+        Clear, ListState, Paragraph, TableState, Wrap,
     },
     widgets::{
         Axis, BarChart, Block, Borders, Chart, Dataset, Gauge, List, ListItem, Row, Sparkline,
@@ -16,83 +27,483 @@ use tui::{
     Frame,
 };
 
+/// Smallest terminal, in columns and rows, the dashboard is drawn in.
+pub const MIN_SIZE: (u16, u16) = (40, 12);
+/// Smallest terminal that fits every panel.
+pub const FULL_SIZE: (u16, u16) = (100, 30);
+/// Columns the Launchers table takes with every column shown.
+const LAUNCHERS_WIDTH: u16 = 43;
+
+/// How much of the dashboard fits into the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Density {
+    /// Only a notice with the required size.
+    TooSmall,
+    /// Logs and the core stress gauge on the first tab, the launcher list
+    /// without the map on the second.
+    Compact,
+    Full,
+}
+
+impl Density {
+    pub fn of(area: Rect) -> Density {
+        let fits = |(width, height): (u16, u16)| area.width >= width && area.height >= height;
+        if fits(FULL_SIZE) {
+            Density::Full
+        } else if fits(MIN_SIZE) {
+            Density::Compact
+        } else {
+            Density::TooSmall
+        }
+    }
+}
+
 pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let density = Density::of(f.size());
+    if density == Density::TooSmall {
+        app.areas = Areas::default();
+        draw_too_small(f, &app.theme);
+        return;
+    }
     let chunks = Layout::default()
         .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
         .split(f.size());
+    app.areas = Areas {
+        tabs: tab_areas(&app.tabs.titles, chunks[0]),
+        ..Areas::default()
+    };
     let titles = app
         .tabs
         .titles
         .iter()
-        .map(|t| Spans::from(Span::styled(*t, Style::default().fg(Color::Green))))
+        .map(|t| Spans::from(Span::styled(*t, Style::default().fg(app.theme.tab))))
         .collect();
+    let speed = if app.paused {
+        " PAUSED ".to_string()
+    } else {
+        format!(" {}x ", app.speed)
+    };
+    let defcon_style = if app.defcon <= 2 {
+        Style::default()
+            .fg(app.theme.alarm_text)
+            .bg(app.theme.alarm)
+    } else {
+        Style::default()
+    };
+    let mut title = vec![
+        Span::raw(app.title),
+        Span::styled(speed, Style::default().add_modifier(Modifier::BOLD)),
+        Span::styled(
+            format!("DEFCON {} ", app.defcon),
+            defcon_style.add_modifier(Modifier::BOLD),
+        ),
+    ];
+    let header = match app.authorization.locked {
+        Some(remaining) => {
+            title.push(Span::styled(
+                format!(
+                    " ALARM: launch panel locked, {} ",
+                    format_duration(remaining.as_secs_f64())
+                ),
+                Style::default()
+                    .fg(app.theme.alarm_text)
+                    .bg(app.theme.alarm)
+                    .add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK),
+            ));
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.alarm))
+                .title(Spans::from(title))
+        }
+        None => Block::default()
+            .borders(Borders::ALL)
+            .title(Spans::from(title)),
+    };
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).title(app.title))
-        .highlight_style(Style::default().fg(Color::Yellow))
+        .block(header)
+        .highlight_style(highlight(app.theme.tab_highlight, app.theme.tab))
         .select(app.tabs.index);
     f.render_widget(tabs, chunks[0]);
     match app.tabs.index {
-        0 => draw_first_tab(f, app, chunks[1]),
-        1 => draw_second_tab(f, app, chunks[1]),
+        0 => draw_first_tab(f, app, chunks[1], density),
+        1 => draw_second_tab(f, app, chunks[1], density),
         _ => {}
     };
 
     if app.typing {
-        let area = centered_rect(60, 20, f.size());
+        let area = popup_rect(60, 30, f.size(), density);
         let block = Block::default()
             .title("Enter Launch Code")
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Magenta))
-            .style(Style::default().bg(Color::Black));
+            .border_style(Style::default().fg(app.theme.popup_border))
+            .style(Style::default().bg(app.theme.popup_background));
 
+        let mut text = vec![
+            Spans::from(vec![Span::styled(
+                help(
+                    &app.keymap,
+                    &[
+                        (Action::Cancel, "exit"),
+                        (Action::Submit, "launch"),
+                        (Action::Delete, "delete"),
+                    ],
+                ),
+                Style::default()
+                    .fg(app.theme.help)
+                    .add_modifier(Modifier::ITALIC),
+            )]),
+            Spans::from(""),
+            Spans::from(vec![
+                Span::from(" "),
+                Span::from("*".repeat(app.code.chars().count())),
+                Span::from(" "),
+            ]),
+            Spans::from(""),
+            match (app.source_launcher(), app.target_name()) {
+                (Some(source), Some(target)) => Spans::from(format!(
+                    "{} -> {} ({})",
+                    source.name,
+                    target,
+                    format_duration(app.flight_distance().unwrap() / MISSILE_SPEED)
+                )),
+                _ => Spans::from(Span::styled(
+                    format!(
+                        "Select a source {} and a target {}/{} on the Launch Missile tab",
+                        app.keymap.label(Action::SetSource),
+                        app.keymap.label(Action::SetTarget),
+                        app.keymap.label(Action::EnterCoords)
+                    ),
+                    Style::default().fg(app.theme.failure),
+                )),
+            },
+            match app.code_accepted {
+                Some(true) => Spans::from(Span::styled(
+                    "Code accepted",
+                    Style::default().fg(app.theme.success),
+                )),
+                Some(false) => Spans::from(Span::styled(
+                    "Incorrect Code!",
+                    Style::default().fg(app.theme.failure),
+                )),
+                None => Spans::from(""),
+            },
+        ];
+        if app.authorization.codes.len() > 1 {
+            let mut operators: Vec<Span> = (0..app.authorization.codes.len())
+                .map(|i| {
+                    if i < app.authorization.accepted {
+                        Span::styled(
+                            format!(" Operator {}: authorized ", i + 1),
+                            Style::default().fg(app.theme.success),
+                        )
+                    } else {
+                        Span::styled(
+                            format!(" Operator {}: pending ", i + 1),
+                            Style::default().fg(app.theme.failure),
+                        )
+                    }
+                })
+                .collect();
+            if let Some(remaining) = app.authorization.remaining {
+                operators.push(Span::raw(format!("({}s left)", remaining.as_secs())));
+            }
+            text.insert(4, Spans::from(operators));
+        }
+
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .style(
+                Style::default()
+                    .fg(app.theme.popup_text)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        // put the paragraph in block
+
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+    }
+
+    if app.entering_target {
+        let area = popup_rect(60, 20, f.size(), density);
+        let block = Block::default()
+            .title("Enter Target Coordinates")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(app.theme.popup_border))
+            .style(Style::default().bg(app.theme.popup_background));
+        let valid = geo::parse_coords(&app.target_input).is_some();
         let text = vec![
             Spans::from(vec![Span::styled(
-                "Press <Esc> to exit | <Enter> to launch | <Backspace> to delete",
+                help(
+                    &app.keymap,
+                    &[
+                        (Action::Cancel, "exit"),
+                        (Action::Submit, "confirm"),
+                        (Action::Delete, "delete"),
+                    ],
+                ),
                 Style::default()
-                    .fg(Color::White)
+                    .fg(app.theme.help)
                     .add_modifier(Modifier::ITALIC),
             )]),
             Spans::from(""),
             Spans::from(vec![
                 Span::from(" "),
-                Span::from(app.code.as_str()),
+                Span::from(app.target_input.as_str()),
                 Span::from(" "),
             ]),
             Spans::from(""),
             Spans::from(vec![Span::styled(
-                if app.code == app.correct_code {
-                    "Correct Code! Press <Enter> to launch!"
+                if valid {
+                    format!("Press {} to set target", app.keymap.label(Action::Submit))
                 } else {
-                    "Incorrect Code!"
+                    "Expected <latitude>, <longitude>".to_string()
                 },
-                Style::default().fg(if app.code == app.correct_code {
-                    Color::Green
+                Style::default().fg(if valid {
+                    app.theme.success
                 } else {
-                    Color::Red
+                    app.theme.failure
                 }),
             )]),
         ];
-
         let paragraph = Paragraph::new(text)
             .block(block)
             .style(
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(app.theme.popup_text)
                     .add_modifier(Modifier::BOLD),
             )
-            .alignment(Alignment::Center);
-
-        // put the paragraph in block
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
 
         f.render_widget(Clear, area);
         f.render_widget(paragraph, area);
     }
+
+    if let Some(sequence) = &app.sequence {
+        draw_countdown(f, app, sequence);
+    }
 }
 
-fn draw_first_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+/// Rows of each glyph in `big_text`.
+const BIG_HEIGHT: u16 = 5;
+
+/// `text` in three column wide block letters, for digits, `T` and `-`.
+fn big_text(text: &str) -> Vec<String> {
+    let glyph = |c: char| -> [&str; BIG_HEIGHT as usize] {
+        match c {
+            '0' => ["███", "█ █", "█ █", "█ █", "███"],
+            '1' => [" █ ", "██ ", " █ ", " █ ", "███"],
+            '2' => ["███", "  █", "███", "█  ", "███"],
+            '3' => ["███", "  █", "███", "  █", "███"],
+            '4' => ["█ █", "█ █", "███", "  █", "  █"],
+            '5' => ["███", "█  ", "███", "  █", "███"],
+            '6' => ["███", "█  ", "███", "█ █", "███"],
+            '7' => ["███", "  █", "  █", "  █", "  █"],
+            '8' => ["███", "█ █", "███", "█ █", "███"],
+            '9' => ["███", "█ █", "███", "  █", "███"],
+            'T' => ["███", " █ ", " █ ", " █ ", " █ "],
+            '-' => ["   ", "   ", "███", "   ", "   "],
+            _ => ["   "; BIG_HEIGHT as usize],
+        }
+    };
+    (0..BIG_HEIGHT as usize)
+        .map(|row| {
+            text.chars()
+                .map(|c| glyph(c)[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// T-minus in big digits over everything else, with the launch steps and
+/// how to abort. Small terminals get the digits as plain text.
+fn draw_countdown<B>(f: &mut Frame<B>, app: &App, sequence: &LaunchSequence)
+where
+    B: Backend,
+{
+    let alarm = Style::default()
+        .fg(app.theme.alarm)
+        .add_modifier(Modifier::BOLD);
+    let tminus = format!("T-{}", sequence.remaining_secs());
+    let steps = LAUNCH_STEPS
+        .iter()
+        .enumerate()
+        .flat_map(|(i, step)| {
+            let (mark, color) = if i < sequence.steps_done() {
+                ("[x]", app.theme.success)
+            } else {
+                ("[ ]", app.theme.help)
+            };
+            [
+                Span::raw(" "),
+                Span::styled(format!("{} {}", mark, step), Style::default().fg(color)),
+                Span::raw(" "),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let source = &app.launchers.items[sequence.source].name;
+    let mut text = vec![
+        Spans::from(steps),
+        Spans::from(format!("{} -> {}", source, sequence.target)),
+        Spans::from(""),
+        Spans::from(Span::styled(
+            help(&app.keymap, &[(Action::Abort, "abort")]),
+            Style::default()
+                .fg(app.theme.help)
+                .add_modifier(Modifier::ITALIC),
+        )),
+    ];
+    let size = f.size();
+    // The digits, a blank line and the borders come on top of the text.
+    let big = size.height >= text.len() as u16 + BIG_HEIGHT + 3;
+    let mut lines: Vec<Spans> = if big {
+        big_text(&tminus)
+            .into_iter()
+            .map(|line| Spans::from(Span::styled(line, alarm)))
+            .collect()
+    } else {
+        vec![Spans::from(Span::styled(format!("{}s", tminus), alarm))]
+    };
+    lines.push(Spans::from(""));
+    lines.append(&mut text);
+    let height = (lines.len() as u16 + 2).min(size.height);
+    let width = 50.min(size.width);
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.y + (size.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .title("Launch Sequence")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.alarm))
+        .style(Style::default().bg(app.theme.popup_background));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .style(Style::default().fg(app.theme.popup_text))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn launcher_glyph(state: LauncherState) -> &'static str {
+    match state {
+        LauncherState::Offline => "○",
+        LauncherState::Booting => "◌",
+        LauncherState::Ready => "●",
+        LauncherState::Armed => "◆",
+        LauncherState::Launching => "▲",
+        LauncherState::Reloading => "↻",
+        LauncherState::Fault => "✖",
+    }
+}
+
+fn launcher_style(state: LauncherState, theme: &Theme) -> Style {
+    match state {
+        LauncherState::Offline => Style::default()
+            .fg(theme.offline)
+            .add_modifier(Modifier::CROSSED_OUT),
+        LauncherState::Booting => Style::default().fg(theme.booting),
+        LauncherState::Ready => Style::default().fg(theme.ready),
+        LauncherState::Armed => Style::default()
+            .fg(theme.armed)
+            .add_modifier(Modifier::BOLD),
+        LauncherState::Launching => Style::default()
+            .fg(theme.launching)
+            .add_modifier(Modifier::BOLD),
+        LauncherState::Reloading => Style::default().fg(theme.reloading),
+        LauncherState::Fault => Style::default()
+            .fg(theme.fault)
+            .add_modifier(Modifier::RAPID_BLINK | Modifier::CROSSED_OUT),
+    }
+}
+
+/// `color`, or reversed video when it cannot be told apart from `other`.
+fn highlight(color: Color, other: Color) -> Style {
+    let style = Style::default().fg(color);
+    if color == other {
+        style.add_modifier(Modifier::REVERSED)
+    } else {
+        style
+    }
+}
+
+/// Help line such as `Press <Esc> to exit | <Enter> to launch` for the
+/// active keymap.
+fn help(keymap: &Keymap, actions: &[(Action, &str)]) -> String {
+    let hints = actions
+        .iter()
+        .map(|&(action, what)| format!("{} to {}", keymap.label(action), what))
+        .collect::<Vec<_>>();
+    format!("Press {}", hints.join(" | "))
+}
+
+/// Format simulated seconds as `12m 05s`.
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    format!("{}m {:02}s", seconds / 60, seconds % 60)
+}
+
+fn draw_too_small<B>(f: &mut Frame<B>, theme: &Theme)
 where
     B: Backend,
 {
+    let size = f.size();
+    let text = vec![
+        Spans::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(theme.failure)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Spans::from(format!("{}x{}", size.width, size.height)),
+        Spans::from(format!("need at least {}x{}", MIN_SIZE.0, MIN_SIZE.1)),
+    ];
+    let area = Rect {
+        y: size.y + size.height.saturating_sub(text.len() as u16) / 2,
+        height: size.height.min(text.len() as u16),
+        ..size
+    };
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, area);
+}
+
+fn draw_first_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect, density: Density)
+where
+    B: Backend,
+{
+    if density == Density::Compact {
+        // The checklist only fits when a few log lines remain below it.
+        let checklist = app.checklist.items.len() as u16 + 2;
+        let checklist = if area.height >= 4 + checklist + 5 {
+            checklist
+        } else {
+            0
+        };
+        let chunks = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length(4),
+                    Constraint::Length(checklist),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        draw_gauges(f, app, chunks[0]);
+        if checklist > 0 {
+            draw_checklist(f, app, chunks[1]);
+        }
+        draw_logs(f, app, chunks[2]);
+        return;
+    }
     let chunks = Layout::default()
         .constraints(
             [
@@ -103,11 +514,59 @@ where
             .as_ref(),
         )
         .split(area);
-    draw_gauges(f, app, chunks[0]);
+    let top = Layout::default()
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .direction(Direction::Horizontal)
+        .split(chunks[0]);
+    draw_gauges(f, app, top[0]);
+    draw_checklist(f, app, top[1]);
     draw_charts(f, app, chunks[1]);
     draw_packets(f, app, chunks[2]);
 }
 
+/// Mandatory items that are still open stand out, the title counts them.
+fn draw_checklist<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let items: Vec<ListItem> = app
+        .checklist
+        .items
+        .iter()
+        .map(|item| {
+            let mark = if item.checked { "[x]" } else { "[ ]" };
+            let style = match (item.checked, item.mandatory) {
+                (true, _) => Style::default().fg(app.theme.success),
+                (false, true) => Style::default()
+                    .fg(app.theme.warning)
+                    .add_modifier(Modifier::BOLD),
+                (false, false) => Style::default(),
+            };
+            let mandatory = if item.mandatory { "*" } else { " " };
+            ListItem::new(Span::styled(
+                format!("{} {}{}", mark, mandatory, item.label),
+                style,
+            ))
+        })
+        .collect();
+    let mandatory = app.checklist.items.iter().filter(|item| item.mandatory);
+    let done = mandatory.clone().filter(|item| item.checked).count();
+    let title = format!(
+        "Checklist {}/{} ({}: toggle)",
+        done,
+        mandatory.count(),
+        app.keymap.label(Action::ToggleItem)
+    );
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    if !app.logs_focused {
+        state.select(app.checklist.state.selected());
+    }
+    f.render_stateful_widget(list, area, &mut state);
+}
+
 fn draw_gauges<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
 where
     B: Backend,
@@ -129,22 +588,36 @@ where
     f.render_widget(block, area);
 
     let label = format!("{:.2}%", app.power);
-    let gauge = Gauge::default()
-        .block(Block::default().title("Core Stress:"))
-        .gauge_style(
-            Style::default()
-                .fg(Color::Magenta)
-                .bg(Color::Black)
-                .add_modifier(Modifier::ITALIC | Modifier::BOLD),
-        )
-        .label(label)
-        .ratio(app.power / 100.0);
-    f.render_widget(gauge, chunks[0]);
+    let style = Style::default()
+        .fg(app.theme.gauge)
+        .bg(app.theme.gauge_background)
+        .add_modifier(Modifier::ITALIC | Modifier::BOLD);
+    if app.theme.gauge == app.theme.gauge_background {
+        // A bar filled by its background is invisible without colors, so
+        // draw it with block characters instead.
+        let width = chunks[0].width as usize;
+        let filled = (width as f64 * app.power / 100.0).round() as usize;
+        let bar = format!("{}{}", "█".repeat(filled), "░".repeat(width - filled));
+        let gauge = Paragraph::new(bar)
+            .block(Block::default().title(format!("Core Stress: {}", label)))
+            .style(style);
+        f.render_widget(gauge, chunks[0]);
+    } else {
+        let gauge = Gauge::default()
+            .block(Block::default().title("Core Stress:"))
+            .gauge_style(style)
+            .label(label)
+            .ratio(app.power / 100.0);
+        f.render_widget(gauge, chunks[0]);
+    }
+    if area.height < 9 {
+        return;
+    }
 
     let sparkline = Sparkline::default()
         .block(Block::default().title("Broadcast Signal Strength:"))
-        .style(Style::default().fg(Color::Green))
-        .data(&app.sparkline.points)
+        .style(Style::default().fg(app.theme.sparkline))
+        .data(app.sparkline.points())
         .bar_set(symbols::bar::NINE_LEVELS);
     f.render_widget(sparkline, chunks[2]);
 }
@@ -158,69 +631,44 @@ where
         .direction(Direction::Horizontal)
         .split(area);
 
-    // Draw logs
-    let info_style = Style::default().fg(Color::Blue);
-    let warning_style = Style::default().fg(Color::Yellow);
-    let error_style = Style::default().fg(Color::Magenta);
-    let critical_style = Style::default().fg(Color::Red);
-    let logs: Vec<ListItem> = app
-        .logs
-        .items
+    draw_logs(f, app, chunks[0]);
+
+    let x_bounds = app.signals.x_bounds();
+    let y_bounds = app.signals.y_bounds();
+    // Per-signal colors would leak color into a monochrome dashboard.
+    let monochrome = app.theme == Theme::monochrome();
+    let datasets = app
+        .signals
+        .list
         .iter()
-        .map(|&(evt, level)| {
-            let s = match level {
-                "ERROR" => error_style,
-                "CRITICAL" => critical_style,
-                "WARNING" => warning_style,
-                _ => info_style,
+        .enumerate()
+        .map(|(i, named)| {
+            let wave = if i % 2 == 0 {
+                app.theme.wave1
+            } else {
+                app.theme.wave2
             };
-            let content = vec![Spans::from(vec![
-                Span::styled(format!("{:<9}", level), s),
-                Span::raw(evt),
-            ])];
-            ListItem::new(content)
+            Dataset::default()
+                .name(named.name.as_str())
+                .marker(match named.marker {
+                    SignalMarker::Dot => symbols::Marker::Dot,
+                    SignalMarker::Block => symbols::Marker::Block,
+                    SignalMarker::Braille => symbols::Marker::Braille,
+                })
+                .style(Style::default().fg(match named.color {
+                    Some(color) if !monochrome => color,
+                    _ => wave,
+                }))
+                .data(named.signal.points())
         })
         .collect();
-    let logs = List::new(logs).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title("System Message"),
-    );
-    f.render_stateful_widget(logs, chunks[0], &mut app.logs.state);
-
-    let x_labels = vec![
-        Span::styled(
-            format!("{}", app.signals.window[0]),
-            Style::default().add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(format!(
-            "{}",
-            (app.signals.window[0] + app.signals.window[1]) / 2.0
-        )),
-        Span::styled(
-            format!("{}", app.signals.window[1]),
-            Style::default().add_modifier(Modifier::BOLD),
-        ),
-    ];
-    let datasets = vec![
-        Dataset::default()
-            .name("CS Wave")
-            .marker(symbols::Marker::Dot)
-            .style(Style::default().fg(Color::Cyan))
-            .data(&app.signals.sin1.points),
-        Dataset::default()
-            .name("IE Wave")
-            .marker(symbols::Marker::Braille)
-            .style(Style::default().fg(Color::Yellow))
-            .data(&app.signals.sin2.points),
-    ];
     let chart = Chart::new(datasets)
         .block(
             Block::default()
                 .title(Span::styled(
                     "Signals",
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(app.theme.chart_title)
                         .add_modifier(Modifier::BOLD),
                 ))
                 .borders(Borders::ALL),
@@ -228,108 +676,408 @@ where
         .x_axis(
             Axis::default()
                 .title("T (cycle)")
-                .style(Style::default().fg(Color::Gray))
-                .bounds(app.signals.window)
-                .labels(x_labels),
+                .style(Style::default().fg(app.theme.axis))
+                .bounds(x_bounds)
+                .labels(axis_labels(x_bounds)),
         )
         .y_axis(
             Axis::default()
                 .title("Strength (dBm)")
-                .style(Style::default().fg(Color::Gray))
-                .bounds([-20.0, 20.0])
-                .labels(vec![
-                    Span::styled("-20", Style::default().add_modifier(Modifier::BOLD)),
-                    Span::raw("0"),
-                    Span::styled("20", Style::default().add_modifier(Modifier::BOLD)),
-                ]),
+                .style(Style::default().fg(app.theme.axis))
+                .bounds(y_bounds)
+                .labels(axis_labels(y_bounds)),
         );
     f.render_widget(chart, chunks[1]);
 }
 
+/// Labels for both ends and the middle of an axis, with as many decimals as
+/// its span needs.
+fn axis_labels(bounds: [f64; 2]) -> Vec<Span<'static>> {
+    let span = bounds[1] - bounds[0];
+    let decimals = if span >= 10.0 {
+        0
+    } else if span >= 1.0 {
+        1
+    } else {
+        2
+    };
+    let label = |value: f64| {
+        let text = format!("{:.*}", decimals, value);
+        // Small negatives round to "-0".
+        if text.parse::<f64>() == Ok(0.0) {
+            format!("{:.*}", decimals, 0.0)
+        } else {
+            text
+        }
+    };
+    vec![
+        Span::styled(
+            label(bounds[0]),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(label((bounds[0] + bounds[1]) / 2.0)),
+        Span::styled(
+            label(bounds[1]),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+    ]
+}
+
+fn draw_logs<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+where
+    B: Backend,
+{
+    let info_style = Style::default().fg(app.theme.info);
+    let warning_style = Style::default().fg(app.theme.warning);
+    let error_style = Style::default().fg(app.theme.error);
+    let critical_style = Style::default().fg(app.theme.critical);
+    let match_style = Style::default()
+        .fg(app.theme.search)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    // Only the newest messages that fit are drawn, or those up to the
+    // selected one when it is further down.
+    let rows = area.height.saturating_sub(2) as usize;
+    let selected = app.logs.state.selected();
+    let mut window: Vec<usize> = app.visible_logs().take(rows).collect();
+    if let Some(i) = selected.filter(|&i| window.last().is_some_and(|&last| i < last)) {
+        window = (i..app.logs.items.len())
+            .filter(|&j| app.shows_log(j))
+            .take(rows)
+            .collect();
+        window.reverse();
+    }
+    let logs: Vec<ListItem> = window
+        .iter()
+        .map(|&i| {
+            let entry = &app.logs.items[i];
+            let s = match entry.level {
+                LogLevel::Info => info_style,
+                LogLevel::Warning => warning_style,
+                LogLevel::Error => error_style,
+                LogLevel::Critical => critical_style,
+            };
+            let mut spans = vec![
+                Span::raw(format!("{} ", entry.clock())),
+                Span::styled(format!("{:<9}", entry.level.as_str()), s),
+            ];
+            spans.extend(highlight_matches(
+                &entry.message,
+                &app.log_search,
+                match_style,
+            ));
+            ListItem::new(vec![Spans::from(spans)])
+        })
+        .collect();
+
+    let mut title = vec![Span::raw("System Message")];
+    if app.logs_paused {
+        title.push(Span::raw(" | paused"));
+    }
+    if let Some(level) = app.log_filter {
+        title.push(Span::raw(format!(" | {}+", level.as_str())));
+    }
+    if app.searching {
+        title.push(Span::styled(
+            format!(" | /{}_", app.log_search),
+            Style::default().fg(app.theme.search),
+        ));
+    } else if !app.log_search.is_empty() {
+        title.push(Span::raw(format!(" | /{}", app.log_search)));
+    }
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(Spans::from(title));
+    if app.logs_focused {
+        block = block.border_style(Style::default().fg(app.theme.tab_highlight));
+    }
+    let logs = List::new(logs)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    state.select(selected.and_then(|i| window.iter().position(|&j| j == i)));
+    app.areas.logs = area;
+    f.render_stateful_widget(logs, area, &mut state);
+}
+
+/// Split `text` into spans, styling every case-insensitive match of `query`.
+fn highlight_matches<'a>(text: &'a str, query: &str, style: Style) -> Vec<Span<'a>> {
+    let lower = text.to_lowercase();
+    let query = query.to_lowercase();
+    // Lowercasing may change byte lengths, then matches cannot be mapped back.
+    if query.is_empty() || lower.len() != text.len() {
+        return vec![Span::raw(text)];
+    }
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (i, _) in lower.match_indices(&query) {
+        if i < start {
+            continue;
+        }
+        spans.push(Span::raw(&text[start..i]));
+        spans.push(Span::styled(&text[i..i + query.len()], style));
+        start = i + query.len();
+    }
+    spans.push(Span::raw(&text[start..]));
+    spans
+}
+
+/// Packets per launcher over the traffic window, with as many bars as fit
+/// the width. When not all launchers fit, the busiest ones are shown.
 fn draw_packets<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
 where
     B: Backend,
 {
+    let launchers = &app.launchers.items;
+    // Locations make short labels, unless two launchers share one.
+    let locations: HashSet<&str> = launchers
+        .iter()
+        .map(|launcher| launcher.location.as_str())
+        .collect();
+    let labels: Vec<&str> = if locations.len() == launchers.len() {
+        launchers
+            .iter()
+            .map(|launcher| launcher.location.as_str())
+            .collect()
+    } else {
+        launchers
+            .iter()
+            .map(|launcher| launcher.name.as_str())
+            .collect()
+    };
+    let label_width = labels
+        .iter()
+        .map(|label| Span::raw(*label).width() as u16)
+        .max()
+        .unwrap_or(0)
+        .clamp(3, 8);
+    let inner = Block::default().borders(Borders::ALL).inner(area).width;
+    let fit = ((inner + 1) / (label_width + 1)) as usize;
+    // Few launchers get wider bars to fill the panel.
+    let bar_width = if fit >= launchers.len() {
+        ((inner + 1) / launchers.len().max(1) as u16 - 1).clamp(label_width, 12)
+    } else {
+        label_width
+    };
+
+    let totals = app.traffic.totals();
+    let mut shown: Vec<usize> = (0..launchers.len()).collect();
+    if shown.len() > fit {
+        shown.sort_by_key(|&i| std::cmp::Reverse(totals[i]));
+        shown.truncate(fit);
+        shown.sort();
+    }
+    let packets: Vec<(&str, u64)> = shown.iter().map(|&i| (labels[i], totals[i])).collect();
+
+    let window = format!("{:.0}s", WINDOW as f64 * TICK_RATE.as_secs_f64());
+    let title = if shown.len() < launchers.len() {
+        format!(
+            "Packets, last {} (busiest {} of {})",
+            window,
+            shown.len(),
+            launchers.len()
+        )
+    } else {
+        format!("Packets, last {}", window)
+    };
     let barchart = BarChart::default()
-        .block(Block::default().borders(Borders::ALL).title("Packets"))
-        .data(&app.packets)
-        .bar_width(3)
-        .bar_gap(2)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .data(&packets)
+        .bar_width(bar_width)
+        .bar_gap(1)
         .bar_set(symbols::bar::NINE_LEVELS)
         .value_style(
             Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
+                .fg(app.theme.bar_value)
+                .bg(app.theme.bar)
                 .add_modifier(Modifier::ITALIC),
         )
-        .label_style(Style::default().fg(Color::Yellow))
-        .bar_style(Style::default().fg(Color::Cyan));
+        .label_style(Style::default().fg(app.theme.bar_label))
+        .bar_style(Style::default().fg(app.theme.bar));
     f.render_widget(barchart, area);
 }
 
-fn draw_second_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect)
+fn draw_second_tab<B>(f: &mut Frame<B>, app: &mut App, area: Rect, density: Density)
 where
     B: Backend,
 {
-    let chunks = Layout::default()
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-        .direction(Direction::Horizontal)
-        .split(area);
-    let up_style = Style::default().fg(Color::Green);
-    let failure_style = Style::default()
-        .fg(Color::Red)
-        .add_modifier(Modifier::RAPID_BLINK | Modifier::CROSSED_OUT);
-    let rows = app.launchers.iter().map(|s| {
-        let style = if s.status == "Up" {
-            up_style
+    let chunks = match density {
+        Density::Full => Layout::default()
+            .constraints(
+                [
+                    Constraint::Length((area.width * 35 / 100).max(LAUNCHERS_WIDTH)),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .direction(Direction::Horizontal)
+            .split(area),
+        _ => Layout::default()
+            .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+            .split(area),
+    };
+    // Without room for every column, the location goes first.
+    let with_location = chunks[0].width >= LAUNCHERS_WIDTH;
+    let rows = app.launchers.items.iter().enumerate().map(|(i, s)| {
+        let style = launcher_style(s.state, &app.theme);
+        let role = if app.source == Some(i) {
+            "SRC"
+        } else if app.target == Some(Target::Launcher(i)) {
+            "TGT"
         } else {
-            failure_style
+            ""
         };
-        Row::new(vec![s.name, s.location, s.status]).style(style)
+        let mut cells = vec![
+            s.name.clone(),
+            s.location.clone(),
+            format!("{} {}", launcher_glyph(s.state), s.state.as_str()),
+            role.to_string(),
+        ];
+        if !with_location {
+            cells.remove(1);
+        }
+        Row::new(cells).style(style)
     });
+    let mut header = vec!["Launcher", "Location", "Status", ""];
+    let mut widths = vec![
+        Constraint::Length(15),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Length(3),
+    ];
+    if !with_location {
+        header.remove(1);
+        widths.remove(1);
+    }
     let table = Table::new(rows)
         .header(
-            Row::new(vec!["Launcher", "Location", "Status"])
-                .style(Style::default().fg(Color::Yellow))
+            Row::new(header)
+                .style(Style::default().fg(app.theme.table_header))
                 .bottom_margin(1),
         )
-        .block(Block::default().title("Launchers").borders(Borders::ALL))
-        .widths(&[
-            Constraint::Length(15),
-            Constraint::Length(15),
-            Constraint::Length(10),
-        ]);
-    f.render_widget(table, chunks[0]);
+        .block(
+            Block::default()
+                .title(format!(
+                    "Launchers ({} src, {} tgt, {} coords)",
+                    app.keymap.label(Action::SetSource),
+                    app.keymap.label(Action::SetTarget),
+                    app.keymap.label(Action::EnterCoords)
+                ))
+                .borders(Borders::ALL),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .widths(&widths);
+    let mut state = TableState::default();
+    state.select(app.launchers.state.selected());
+    f.render_stateful_widget(table, chunks[0], &mut state);
+    // The table scrolls just far enough to keep the selection in view, the
+    // rows start below the border, header and its margin.
+    let inner = Block::default().borders(Borders::ALL).inner(chunks[0]);
+    let rows = Rect {
+        y: inner.y + 2,
+        height: inner.height.saturating_sub(2),
+        ..inner
+    };
+    app.areas.launchers = rows;
+    app.areas.launchers_offset = app
+        .launchers
+        .state
+        .selected()
+        .map_or(0, |i| (i + 1).saturating_sub(rows.height as usize));
+
+    if density != Density::Full {
+        let status = flight_status(app).unwrap_or_else(|| "No trajectory planned".to_string());
+        f.render_widget(Paragraph::new(status), chunks[1]);
+        return;
+    }
 
+    let title = match flight_status(app) {
+        Some(status) => format!("World Map | {}", status),
+        None => "World Map".to_string(),
+    };
     let map = Canvas::default()
-        .block(Block::default().title("World Map").borders(Borders::ALL))
+        .block(Block::default().title(title).borders(Borders::ALL))
         .paint(|ctx| {
             ctx.draw(&Map {
-                color: Color::White,
+                color: app.theme.map,
                 resolution: MapResolution::High,
             });
             ctx.layer();
 
-            for launcher in &app.launchers {
-                let color = if launcher.status == "Up" {
-                    Color::Green
-                } else {
-                    Color::Red
+            // A launch under way is drawn over a missile still in flight.
+            let trajectory = match (&app.sequence, &app.missile) {
+                (Some(sequence), _) => Some((sequence.from, sequence.to)),
+                (None, Some(missile)) => Some((missile.from, missile.to)),
+                (None, None) => app.trajectory(),
+            };
+            if let Some((from, to)) = trajectory {
+                let arc: Vec<(f64, f64)> = geo::arc(from, to, 200)
+                    .into_iter()
+                    .map(|(lat, lon)| (lon, lat))
+                    .collect();
+                let flown = match (&app.sequence, &app.missile) {
+                    (None, Some(missile)) => (missile.fraction() * 200.0) as usize + 1,
+                    _ => 0,
                 };
+                let (trail, planned) = arc.split_at(flown);
+                ctx.draw(&Points {
+                    coords: planned,
+                    color: app.theme.trajectory,
+                });
+                ctx.draw(&Points {
+                    coords: trail,
+                    color: app.theme.trail,
+                });
+                ctx.layer();
+            }
+
+            for launcher in &app.launchers.items {
                 ctx.print(
                     launcher.coords.1,
                     launcher.coords.0,
-                    Span::styled("x", Style::default().fg(color)),
+                    Span::styled(
+                        launcher_glyph(launcher.state),
+                        launcher_style(launcher.state, &app.theme),
+                    ),
                 );
             }
 
+            if let Some((lat, lon)) = app.target_coords() {
+                ctx.print(
+                    lon,
+                    lat,
+                    Span::styled("⊕", Style::default().fg(app.theme.target)),
+                );
+            }
+
+            if let Some(missile) = &app.missile {
+                let (lat, lon) = missile.position();
+                if missile.has_impacted() {
+                    ctx.print(
+                        lon,
+                        lat,
+                        Span::styled(
+                            "✸ IMPACT",
+                            Style::default()
+                                .fg(app.theme.missile)
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                    );
+                } else {
+                    ctx.print(
+                        lon,
+                        lat,
+                        Span::styled("●", Style::default().fg(app.theme.missile)),
+                    );
+                }
+            }
+
             if !app.missile_launched {
                 ctx.draw(&Line {
                     x1: -180.0,
                     y1: -5.0,
                     x2: 180.0,
                     y2: 25.0,
-                    color: Color::Cyan,
+                    color: app.theme.satellite,
                 });
 
                 ctx.draw(&Rectangle {
@@ -337,7 +1085,7 @@ where
                     y: 7.0,
                     width: 20.0,
                     height: 20.0,
-                    color: Color::Cyan,
+                    color: app.theme.satellite,
                 });
 
                 ctx.print(100.0, 17.0, Span::styled("🛰️", Style::default()));
@@ -347,6 +1095,69 @@ where
         .x_bounds([-180.0, 180.0])
         .y_bounds([-90.0, 90.0]);
     f.render_widget(map, chunks[1]);
+    app.areas.map = Block::default().borders(Borders::ALL).inner(chunks[1]);
+}
+
+/// Countdown of the launch under way, progress of the missile, or the planned
+/// trajectory before launch.
+fn flight_status(app: &App) -> Option<String> {
+    match (
+        &app.sequence,
+        &app.missile,
+        app.source_launcher(),
+        app.target_name(),
+    ) {
+        (Some(sequence), _, _, _) => Some(format!(
+            "{} -> {} | T-{}s",
+            app.launchers.items[sequence.source].name,
+            sequence.target,
+            sequence.remaining_secs()
+        )),
+        (None, Some(missile), _, _) if missile.has_impacted() => {
+            Some(format!("Impact at {}", missile.target))
+        }
+        (None, Some(missile), _, _) => Some(format!(
+            "Inbound {} | T+ {} / {}",
+            missile.target,
+            format_duration(missile.elapsed()),
+            format_duration(missile.flight_time())
+        )),
+        (None, None, Some(source), Some(target)) => {
+            let distance = app.flight_distance().unwrap();
+            Some(format!(
+                "{} -> {} | {:.0} km | ETA {}",
+                source.name,
+                target,
+                distance,
+                format_duration(distance / MISSILE_SPEED)
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Where `Tabs` puts each title inside its bordered block: one space of
+/// padding on both sides and a one column divider in between.
+fn tab_areas(titles: &[&str], area: Rect) -> Vec<Rect> {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    let mut x = inner.x;
+    titles
+        .iter()
+        .map(|title| {
+            let width = Span::raw(*title).width() as u16;
+            let tab = Rect::new(x + 1, inner.y, width, 1).intersection(inner);
+            x += width + 3;
+            tab
+        })
+        .collect()
+}
+
+/// A centered popup, taking most of the screen when space is short.
+fn popup_rect(percent_x: u16, percent_y: u16, r: Rect, density: Density) -> Rect {
+    match density {
+        Density::Full => centered_rect(percent_x, percent_y, r),
+        _ => centered_rect(90, 70, r),
+    }
 }
 
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
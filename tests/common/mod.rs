@@ -0,0 +1,43 @@
+//! The dashboard every integration test starts from: a single operator
+//! with `CODE` and the pre-launch checklist ticked off.
+
+// Every test crate compiles this module and uses only some of it.
+#![allow(dead_code)]
+
+use missile_launcher::{
+    app::App,
+    auth::{Authorization, CodeHash},
+    headless::Headless,
+    scenario::Scenario,
+};
+use std::time::Duration;
+
+pub const CODE: &str = "1234";
+
+pub struct Fixture {
+    seed: u64,
+}
+
+impl Fixture {
+    pub fn new() -> Fixture {
+        Fixture { seed: 1 }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Fixture {
+        self.seed = seed;
+        self
+    }
+
+    pub fn app(self, scenario: &Scenario) -> App<'_> {
+        let authorization = Authorization::new(vec![CodeHash::new(CODE)], Duration::from_secs(30));
+        let mut app = App::new("Missile Launcher", authorization, scenario, self.seed);
+        for item in &mut app.checklist.items {
+            item.checked = true;
+        }
+        app
+    }
+
+    pub fn headless(self, scenario: &Scenario, width: u16, height: u16) -> Headless<'_> {
+        Headless::new(self.app(scenario), width, height).unwrap()
+    }
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::{Fixture, CODE};
+use crossterm::event::KeyCode;
+use missile_launcher::{
+    app::LauncherState,
+    headless::{to_text, Headless},
+    logs::LogLevel,
+    scenario::{LogMessage, Scenario},
+};
+
+fn headless(scenario: &Scenario) -> Headless<'_> {
+    Fixture::new().headless(scenario, 100, 30)
+}
+
+/// The built-in launchers, without random messages that could fault them.
+fn quiet() -> Scenario {
+    Scenario {
+        logs: vec![LogMessage {
+            message: "Routine check".to_string(),
+            level: LogLevel::Info,
+        }],
+        ..Scenario::default()
+    }
+}
+
+fn state(headless: &Headless, i: usize) -> LauncherState {
+    headless.app.launchers.items[i].state
+}
+
+/// Pick launcher `source` to fire at launcher `target` on the Launch Missile
+/// tab.
+fn aim(headless: &mut Headless, source: usize, target: usize) {
+    headless.app.launchers.state.select(Some(source));
+    headless.press(KeyCode::Char('s'));
+    headless.app.launchers.state.select(Some(target));
+    headless.press(KeyCode::Char('d'));
+}
+
+fn type_code(headless: &mut Headless, code: &str) {
+    for c in code.chars() {
+        headless.press(KeyCode::Char(c));
+    }
+    headless.press(KeyCode::Enter);
+}
+
+#[test]
+fn launch_panel_stays_shut_without_a_trajectory() {
+    let scenario = Scenario::default();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Char('t'));
+    assert!(!headless.app.typing);
+    assert!(headless
+        .app
+        .logs
+        .items
+        .last()
+        .unwrap()
+        .message
+        .contains("pick a source and a target"));
+}
+
+#[test]
+fn accepted_codes_start_the_countdown_and_are_spent() {
+    let scenario = Scenario::default();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+
+    headless.press(KeyCode::Char('t'));
+    assert!(headless.app.typing);
+    type_code(&mut headless, CODE);
+    assert!(headless.app.sequence.is_some());
+    assert!(!headless.app.typing);
+    assert_eq!(headless.app.authorization.accepted, 0);
+    assert_eq!(headless.app.authorization.failures, 0);
+}
+
+#[test]
+fn a_new_target_replaces_the_impact_on_the_map() {
+    let scenario = Scenario::default();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+    headless.press(KeyCode::Char('t'));
+    type_code(&mut headless, CODE);
+    while !headless
+        .app
+        .missile
+        .as_ref()
+        .is_some_and(|missile| missile.has_impacted())
+    {
+        headless.tick(1);
+    }
+    assert!(to_text(headless.draw().unwrap()).contains("Impact at"));
+
+    aim(&mut headless, 0, 2);
+    assert!(headless.app.missile.is_none());
+    let target = headless.app.launchers.items[2].name.clone();
+    let screen = to_text(headless.draw().unwrap());
+    assert!(screen.contains(&format!("-> {} |", target)), "{}", screen);
+}
+
+#[test]
+fn typing_into_the_log_search_does_not_abort_the_countdown() {
+    let scenario = Scenario::default();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+    headless.press(KeyCode::Char('t'));
+    type_code(&mut headless, CODE);
+    headless.press(KeyCode::Left);
+    headless.press(KeyCode::Char('/'));
+    for c in "launch".chars() {
+        headless.press(KeyCode::Char(c));
+    }
+    assert_eq!(headless.app.log_search, "launch");
+    assert!(headless.app.sequence.is_some());
+
+    headless.press(KeyCode::Enter);
+    headless.press(KeyCode::Char('a'));
+    assert!(headless.app.sequence.is_none());
+}
+
+#[test]
+fn the_lockout_runs_in_real_time() {
+    let scenario = Scenario::default();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+    for _ in 0..headless.app.authorization.max_failures {
+        headless.press(KeyCode::Char('t'));
+        type_code(&mut headless, "0000");
+    }
+    let locked = headless.app.authorization.locked.unwrap();
+
+    let ticks = headless.app.ticks;
+    headless.press(KeyCode::Char('n'));
+    headless.press(KeyCode::Char('z'));
+    assert_eq!(headless.app.ticks, ticks);
+    assert!(!headless.app.paused);
+
+    while headless.app.speed != 8 {
+        headless.press(KeyCode::Char('v'));
+    }
+    headless.tick(80);
+    let elapsed = headless.app.tick_rate * 10;
+    assert_eq!(headless.app.authorization.locked, Some(locked - elapsed));
+}
+
+#[test]
+fn picking_a_source_arms_it_and_disarms_the_previous_one() {
+    let scenario = quiet();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+    assert_eq!(state(&headless, 0), LauncherState::Armed);
+    assert_eq!(state(&headless, 1), LauncherState::Ready);
+
+    // USA-2 starts out offline and cannot be armed.
+    aim(&mut headless, 2, 1);
+    assert_eq!(state(&headless, 0), LauncherState::Ready);
+    assert_eq!(state(&headless, 2), LauncherState::Offline);
+}
+
+#[test]
+fn lift_off_launches_and_reloads_the_source() {
+    let scenario = quiet();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+    headless.press(KeyCode::Char('t'));
+    type_code(&mut headless, CODE);
+    while headless.app.sequence.is_some() {
+        assert_eq!(state(&headless, 0), LauncherState::Armed);
+        headless.tick(1);
+    }
+    assert_eq!(state(&headless, 0), LauncherState::Launching);
+    assert!(headless.app.missile.is_some());
+    headless.tick(30);
+    assert_eq!(state(&headless, 0), LauncherState::Reloading);
+    headless.tick(150);
+    assert_eq!(state(&headless, 0), LauncherState::Ready);
+}
+
+#[test]
+fn a_launcher_faulting_during_the_countdown_does_not_fire() {
+    let scenario = quiet();
+    let mut headless = headless(&scenario);
+    headless.press(KeyCode::Right);
+    aim(&mut headless, 0, 1);
+    headless.press(KeyCode::Char('t'));
+    type_code(&mut headless, CODE);
+    headless.app.launchers.items[0].set_state(LauncherState::Fault);
+    while headless.app.sequence.is_some() {
+        headless.tick(1);
+    }
+    assert_eq!(state(&headless, 0), LauncherState::Fault);
+    assert!(headless.app.missile.is_none());
+    assert!(headless
+        .app
+        .logs
+        .items
+        .iter()
+        .any(|entry| entry.message.contains("aborted at T-0")));
+}
@@ -0,0 +1,84 @@
+//! The layout breakpoints, drawn on a `TestBackend` of every size around
+//! them.
+
+mod common;
+
+use common::Fixture;
+use crossterm::event::KeyCode;
+use missile_launcher::{
+    headless::to_text,
+    scenario::Scenario,
+    ui::{Density, FULL_SIZE, MIN_SIZE},
+};
+use tui::layout::Rect;
+
+fn draw(scenario: &Scenario, width: u16, height: u16, tab: usize) -> String {
+    let mut headless = Fixture::new().headless(scenario, width, height);
+    for _ in 0..tab {
+        headless.press(KeyCode::Right);
+    }
+    headless.tick(10);
+    to_text(headless.draw().unwrap())
+}
+
+#[test]
+fn density_changes_at_the_breakpoints() {
+    let density = |(width, height)| Density::of(Rect::new(0, 0, width, height));
+    let (full_width, full_height) = FULL_SIZE;
+    let (min_width, min_height) = MIN_SIZE;
+    assert_eq!(density(FULL_SIZE), Density::Full);
+    assert_eq!(density((full_width + 50, full_height + 20)), Density::Full);
+    assert_eq!(density((full_width - 1, full_height)), Density::Compact);
+    assert_eq!(density((full_width, full_height - 1)), Density::Compact);
+    assert_eq!(density(MIN_SIZE), Density::Compact);
+    assert_eq!(density((min_width - 1, min_height)), Density::TooSmall);
+    assert_eq!(density((min_width, min_height - 1)), Density::TooSmall);
+    assert_eq!(density((0, 0)), Density::TooSmall);
+}
+
+#[test]
+fn full_layout_shows_the_charts_and_the_map() {
+    let scenario = Scenario::default();
+    let (width, height) = FULL_SIZE;
+    let monitor = draw(&scenario, width, height, 0);
+    assert!(monitor.contains("Signals"));
+    assert!(monitor.contains("Packets"));
+    let launch = draw(&scenario, width, height, 1);
+    assert!(launch.contains("World Map"), "{}", launch);
+    assert!(launch.contains("Location"), "{}", launch);
+    assert!(launch.contains("coords)"), "{}", launch);
+}
+
+#[test]
+fn compact_layout_leaves_out_the_charts_and_the_map() {
+    let scenario = Scenario::default();
+    for (width, height) in [MIN_SIZE, (FULL_SIZE.0 - 1, FULL_SIZE.1)] {
+        let monitor = draw(&scenario, width, height, 0);
+        assert!(monitor.contains("System Message"), "{}", monitor);
+        assert!(!monitor.contains("Signals"), "{}", monitor);
+        assert!(!monitor.contains("Packets"), "{}", monitor);
+        let launch = draw(&scenario, width, height, 1);
+        assert!(launch.contains("Launchers"), "{}", launch);
+        assert!(!launch.contains("World Map"), "{}", launch);
+    }
+}
+
+#[test]
+fn too_small_shows_the_required_size() {
+    let scenario = Scenario::default();
+    let text = draw(&scenario, MIN_SIZE.0 - 1, MIN_SIZE.1, 0);
+    assert!(text.contains("Terminal too small"), "{}", text);
+    assert!(text.contains(&format!("need at least {}x{}", MIN_SIZE.0, MIN_SIZE.1)));
+}
+
+#[test]
+fn every_size_up_to_full_draws() {
+    let scenario = Scenario::default();
+    for width in (0..=FULL_SIZE.0).step_by(3) {
+        for height in (0..=FULL_SIZE.1).step_by(3) {
+            for tab in 0..2 {
+                draw(&scenario, width, height, tab);
+            }
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use missile_launcher::scenario::{Scenario, ScenarioError};
+
+/// Launchers and logs a scenario needs before anything else is checked.
+const BASE: &str = r#"
+[[launchers]]
+name = "Asia-1"
+location = "TPE"
+coords = [25.0, 121.5]
+state = "Ready"
+
+[[launchers]]
+name = "USA-1"
+location = "LAX"
+coords = [34.0, -118.2]
+state = "Offline"
+
+[[logs]]
+message = "TPE launch system is ready and stable"
+level = "INFO"
+"#;
+
+fn problems(toml: &str) -> Vec<String> {
+    let scenario: Scenario = toml::from_str(toml).unwrap();
+    match scenario.validate() {
+        Ok(()) => Vec::new(),
+        Err(ScenarioError::Invalid(problems)) => problems,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+#[test]
+fn shipped_scenarios_are_valid() {
+    Scenario::default().validate().unwrap();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios/training.toml");
+    Scenario::load(path).unwrap();
+    assert!(problems(BASE).is_empty());
+}
+
+#[test]
+fn empty_scenario_needs_launchers_and_logs() {
+    assert_eq!(
+        problems("launchers = []\nlogs = []"),
+        [
+            "at least one launcher is required",
+            "at least one log message is required"
+        ]
+    );
+}
+
+#[test]
+fn launcher_problems_are_all_reported() {
+    let toml = r#"
+        logs = [{ message = "ok", level = "INFO" }]
+
+        [[launchers]]
+        name = "Asia-1"
+        location = "TPE"
+        coords = [91.0, 0.0]
+        state = "Ready"
+
+        [[launchers]]
+        name = "Asia-1"
+        location = ""
+        coords = [0.0, -180.5]
+        state = "Ready"
+
+        [[launchers]]
+        name = ""
+        location = "LAX"
+        coords = [0.0, 0.0]
+        state = "Ready"
+    "#;
+    assert_eq!(
+        problems(toml),
+        [
+            "launchers[0]: latitude 91 is outside [-90, 90]",
+            "launchers[1]: duplicate name \"Asia-1\"",
+            "launchers[1]: location is empty",
+            "launchers[1]: longitude -180.5 is outside [-180, 180]",
+            "launchers[2]: name is empty",
+        ]
+    );
+}
+
+#[test]
+fn packets_need_a_launcher_at_their_source() {
+    let toml = format!(
+        "{}{}",
+        BASE,
+        r#"
+        [[packets]]
+        source = "TPE"
+        rate = 9
+
+        [[packets]]
+        source = "TPE"
+        rate = 3
+
+        [[packets]]
+        source = "JFK"
+        rate = 8
+
+        [[packets]]
+        source = ""
+        rate = 1
+        "#
+    );
+    assert_eq!(
+        problems(&toml),
+        [
+            "packets[1]: duplicate source \"TPE\"",
+            "packets[2]: no launcher at \"JFK\"",
+            "packets[3]: source is empty",
+        ]
+    );
+}
+
+#[test]
+fn checklist_labels_are_unique() {
+    let toml = format!(
+        "{}{}",
+        r#"
+        checklist = [
+            { label = "Brief the crew" },
+            { label = "Brief the crew", mandatory = true },
+            { label = "" },
+        ]
+        "#,
+        BASE
+    );
+    assert_eq!(
+        problems(&toml),
+        [
+            "checklist[1]: duplicate label \"Brief the crew\"",
+            "checklist[2]: label is empty",
+        ]
+    );
+}
+
+#[test]
+fn signal_sources_are_checked_all_the_way_down() {
+    let toml = format!(
+        "{}{}",
+        r#"
+        chart = { y_bounds = [10.0, -10.0] }
+
+        [[signals]]
+        name = "Uplink"
+        color = "mauve"
+        samples = 0
+        source = { kind = "sine", period = 0, amplitude = 1, noise = 2 }
+
+        [[signals]]
+        name = "Uplink"
+        source = { kind = "sum", sources = [
+            { kind = "chirp", from = 10, to = -2, sweep = 40, amplitude = 8 },
+            { kind = "sum", sources = [] },
+        ] }
+        "#,
+        BASE
+    );
+    assert_eq!(
+        problems(&toml),
+        [
+            "signals[0]: unknown color \"mauve\"",
+            "signals[0]: samples 0 is outside [1, 100]",
+            "signals[0].source.period: 0 is not positive",
+            "signals[0].source.noise: 2 is outside [0, 1]",
+            "signals[1]: duplicate name \"Uplink\"",
+            "signals[1].source.sources[0].to: -2 is not positive",
+            "signals[1].source.sources[1].sources: at least one source is required",
+            "chart.y_bounds: [10, -10] is not an increasing range",
+        ]
+    );
+}
+
+#[test]
+fn timeline_events_must_be_well_formed() {
+    let toml = format!(
+        "{}{}",
+        BASE,
+        r#"
+        [[timeline]]
+        at = "01:75"
+        defcon = 3
+
+        [[timeline]]
+        at = "00:10"
+        launcher = "JFK"
+        state = "Fault"
+
+        [[timeline]]
+        at = "00:20"
+        launcher = "TPE"
+
+        [[timeline]]
+        at = "00:30"
+        log = { level = "INFO", message = "" }
+        defcon = 0
+
+        [[timeline]]
+        at = "00:40"
+        "#
+    );
+    assert_eq!(
+        problems(&toml),
+        [
+            "timeline[0]: at \"01:75\" is not mm:ss or hh:mm:ss",
+            "timeline[1]: no launcher named or at \"JFK\"",
+            "timeline[2]: launcher and state go together",
+            "timeline[2]: does nothing",
+            "timeline[3]: log message is empty",
+            "timeline[3]: defcon 0 is outside [1, 5]",
+            "timeline[4]: does nothing",
+        ]
+    );
+}
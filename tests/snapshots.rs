@@ -0,0 +1,77 @@
+//! Golden snapshots of the dashboard, rendered headless at the full layout.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to write the current renders to
+//! `tests/snapshots/` after an intended change, then review the diff.
+
+mod common;
+
+use common::{Fixture, CODE};
+use crossterm::event::KeyCode;
+use missile_launcher::{
+    headless::{render, to_text, Step},
+    scenario::Scenario,
+};
+use std::{env, fs, path::PathBuf};
+
+fn key(code: KeyCode) -> Step {
+    Step::Key(code.into())
+}
+
+/// Pick the first launcher as source and the second as target, then open the
+/// launch panel.
+fn open_launch_panel() -> Vec<Step> {
+    vec![
+        key(KeyCode::Right),
+        key(KeyCode::Down),
+        key(KeyCode::Char('s')),
+        key(KeyCode::Down),
+        key(KeyCode::Char('d')),
+        key(KeyCode::Char('t')),
+    ]
+}
+
+fn assert_snapshot(name: &str, steps: &[Step]) {
+    let scenario = Scenario::default();
+    let buffer = render(Fixture::new().seed(42).app(&scenario), 100, 30, steps).unwrap();
+    let text = to_text(&buffer);
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &text).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("{}: {}, run with UPDATE_SNAPSHOTS=1", path.display(), err));
+    assert!(
+        text == expected,
+        "{} changed, run with UPDATE_SNAPSHOTS=1 if intended:\n{}",
+        name,
+        text
+    );
+}
+
+#[test]
+fn system_monitor() {
+    assert_snapshot("system_monitor", &[Step::Ticks(50)]);
+}
+
+#[test]
+fn launch_missile() {
+    assert_snapshot("launch_missile", &[key(KeyCode::Right), Step::Ticks(50)]);
+}
+
+#[test]
+fn launch_panel() {
+    let mut steps = open_launch_panel();
+    steps.extend([key(KeyCode::Char('1')), key(KeyCode::Char('2'))]);
+    assert_snapshot("launch_panel", &steps);
+}
+
+#[test]
+fn missile_in_flight() {
+    let mut steps = open_launch_panel();
+    steps.extend(CODE.chars().map(|c| key(KeyCode::Char(c))));
+    steps.extend([key(KeyCode::Enter), Step::Ticks(110)]);
+    assert_snapshot("missile_in_flight", &steps);
+}
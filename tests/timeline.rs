@@ -0,0 +1,158 @@
+//! Scripted timeline events fired into a running dashboard.
+
+mod common;
+
+use common::Fixture;
+use missile_launcher::{
+    app::LauncherState,
+    audit::AuditLog,
+    headless::Headless,
+    logs::LogLevel,
+    scenario::{LogMessage, Scenario, TimelineEvent},
+};
+use std::{env, fs, process};
+
+/// The built-in launchers, with `timeline` and a random message that never
+/// mentions any of them.
+fn scenario(timeline: Vec<TimelineEvent>) -> Scenario {
+    Scenario {
+        logs: vec![LogMessage {
+            message: "Routine check".to_string(),
+            level: LogLevel::Info,
+        }],
+        timeline,
+        ..Scenario::default()
+    }
+}
+
+/// An event at `at` that does nothing until given something to do.
+fn event(at: &str) -> TimelineEvent {
+    TimelineEvent {
+        at: at.to_string(),
+        launcher: None,
+        state: None,
+        log: None,
+        defcon: None,
+    }
+}
+
+fn log(message: &str) -> Option<LogMessage> {
+    Some(LogMessage {
+        message: message.to_string(),
+        level: LogLevel::Critical,
+    })
+}
+
+fn messages<'a>(headless: &'a Headless, wanted: &[&str]) -> Vec<&'a str> {
+    headless
+        .app
+        .logs
+        .items
+        .iter()
+        .map(|entry| entry.message.as_str())
+        .filter(|message| wanted.contains(message))
+        .collect()
+}
+
+fn state(headless: &Headless, name: &str) -> LauncherState {
+    let launchers = &headless.app.launchers.items;
+    launchers.iter().find(|l| l.name == name).unwrap().state
+}
+
+#[test]
+fn events_change_launchers_log_and_set_the_defcon() {
+    let scenario = scenario(vec![
+        TimelineEvent {
+            launcher: Some("JFK".to_string()),
+            state: Some(LauncherState::Ready),
+            defcon: Some(2),
+            ..event("00:01")
+        },
+        TimelineEvent {
+            launcher: Some("Asia-1".to_string()),
+            state: Some(LauncherState::Offline),
+            log: log("Intrusion detected"),
+            ..event("00:02")
+        },
+    ]);
+    let mut headless = Fixture::new().headless(&scenario, 100, 30);
+
+    headless.tick(9);
+    assert_eq!(headless.app.defcon, 5);
+    assert_eq!(state(&headless, "USA-2"), LauncherState::Offline);
+    headless.tick(1);
+    assert_eq!(headless.app.defcon, 2);
+    assert_eq!(state(&headless, "USA-2"), LauncherState::Ready);
+
+    headless.tick(10);
+    assert_eq!(state(&headless, "Asia-1"), LauncherState::Offline);
+    assert_eq!(messages(&headless, &["Intrusion detected"]).len(), 1);
+}
+
+#[test]
+fn the_log_comes_after_the_state() {
+    // A critical message about a launcher faults it, whatever the event set.
+    let scenario = scenario(vec![TimelineEvent {
+        launcher: Some("LAX".to_string()),
+        state: Some(LauncherState::Offline),
+        log: log("Power outage at LAX"),
+        ..event("00:01")
+    }]);
+    let mut headless = Fixture::new().headless(&scenario, 100, 30);
+    headless.tick(10);
+    assert_eq!(state(&headless, "USA-1"), LauncherState::Fault);
+}
+
+#[test]
+fn events_sharing_a_tick_fire_in_order() {
+    let scenario = scenario(vec![
+        TimelineEvent {
+            log: log("second"),
+            defcon: Some(3),
+            ..event("00:00.15")
+        },
+        TimelineEvent {
+            log: log("first"),
+            defcon: Some(4),
+            ..event("00:00.11")
+        },
+    ]);
+    let mut headless = Fixture::new().headless(&scenario, 100, 30);
+    headless.tick(1);
+    assert!(messages(&headless, &["first", "second"]).is_empty());
+    headless.tick(1);
+    assert_eq!(
+        messages(&headless, &["first", "second"]),
+        ["first", "second"]
+    );
+    assert_eq!(headless.app.defcon, 3);
+
+    headless.tick(100);
+    assert_eq!(messages(&headless, &["first", "second"]).len(), 2);
+    assert_eq!(headless.app.defcon, 3);
+}
+
+#[test]
+fn fired_events_are_audited() {
+    let path = env::temp_dir().join(format!("timeline-{}.jsonl", process::id()));
+    let _ = fs::remove_file(&path);
+    let scenario = scenario(vec![TimelineEvent {
+        launcher: Some("LAX".to_string()),
+        state: Some(LauncherState::Fault),
+        log: log("Power outage at LAX"),
+        defcon: Some(2),
+        ..event("00:01")
+    }]);
+    let mut headless = Fixture::new().headless(&scenario, 100, 30);
+    headless.app.audit = Some(AuditLog::open(&path).unwrap());
+    headless.tick(10);
+    headless.app.audit = None;
+
+    let trail = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(
+        trail.contains("00:01: LAX Fault, log CRITICAL, DEFCON 2"),
+        "{}",
+        trail
+    );
+}